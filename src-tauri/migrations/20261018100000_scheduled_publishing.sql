@@ -0,0 +1,25 @@
+-- Posts can now exist as drafts; everything already in the table stays live.
+ALTER TABLE tadgh_blog.blog_posts
+    ADD COLUMN published BOOLEAN NOT NULL DEFAULT TRUE;
+
+CREATE TABLE tadgh_blog.scheduled_publications (
+    id SERIAL PRIMARY KEY,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('blog_post', 'project')),
+    entity_id INTEGER NOT NULL,
+    publish_at TIMESTAMPTZ NOT NULL,
+    run_deploy_hook BOOLEAN NOT NULL DEFAULT FALSE,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'published', 'cancelled', 'failed')),
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    published_at TIMESTAMPTZ
+);
+
+-- Only one pending schedule per post or project
+CREATE UNIQUE INDEX scheduled_publications_pending_idx
+    ON tadgh_blog.scheduled_publications (entity_type, entity_id)
+    WHERE status = 'pending';
+
+CREATE INDEX scheduled_publications_due_idx
+    ON tadgh_blog.scheduled_publications (publish_at)
+    WHERE status = 'pending';
@@ -0,0 +1,13 @@
+CREATE TABLE tadgh_blog.revisions (
+    id SERIAL PRIMARY KEY,
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('blog_post', 'project')),
+    entity_id INTEGER NOT NULL,
+    -- Full row as it was after the change, see to_jsonb() in revisions.rs
+    snapshot JSONB NOT NULL,
+    -- Markdown body from the blog folder, when it could be read
+    body TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX revisions_entity_idx
+    ON tadgh_blog.revisions (entity_type, entity_id, created_at DESC);
@@ -0,0 +1,81 @@
+CREATE FUNCTION tadgh_blog.slugify(value TEXT) RETURNS TEXT
+LANGUAGE sql IMMUTABLE AS $$
+    SELECT trim(BOTH '-' FROM lower(regexp_replace(value, '[^a-zA-Z0-9]+', '-', 'g')))
+$$;
+
+ALTER TABLE tadgh_blog.blog_posts ADD COLUMN slug TEXT;
+ALTER TABLE tadgh_blog.projects ADD COLUMN slug TEXT;
+ALTER TABLE tadgh_blog.tags ADD COLUMN slug TEXT;
+ALTER TABLE tadgh_blog.categories ADD COLUMN slug TEXT;
+
+-- Posts keep the file name they were served under, everything else uses its title/name.
+-- Duplicates after the first get the row id appended.
+UPDATE tadgh_blog.blog_posts bp
+SET slug = s.slug
+FROM (
+    SELECT id,
+           CASE WHEN ROW_NUMBER() OVER (PARTITION BY base ORDER BY id) = 1
+                THEN base ELSE base || '-' || id END AS slug
+    FROM (
+        SELECT id,
+               COALESCE(NULLIF(tadgh_blog.slugify(regexp_replace(file_name, '\.[^.]*$', '')), ''), 'post') AS base
+        FROM tadgh_blog.blog_posts
+    ) b
+) s
+WHERE bp.id = s.id;
+
+UPDATE tadgh_blog.projects p
+SET slug = s.slug
+FROM (
+    SELECT id,
+           CASE WHEN ROW_NUMBER() OVER (PARTITION BY base ORDER BY id) = 1
+                THEN base ELSE base || '-' || id END AS slug
+    FROM (
+        SELECT id, COALESCE(NULLIF(tadgh_blog.slugify(title), ''), 'project') AS base
+        FROM tadgh_blog.projects
+    ) b
+) s
+WHERE p.id = s.id;
+
+UPDATE tadgh_blog.tags t
+SET slug = s.slug
+FROM (
+    SELECT id,
+           CASE WHEN ROW_NUMBER() OVER (PARTITION BY base ORDER BY id) = 1
+                THEN base ELSE base || '-' || id END AS slug
+    FROM (
+        SELECT id, COALESCE(NULLIF(tadgh_blog.slugify(name), ''), 'tag') AS base
+        FROM tadgh_blog.tags
+    ) b
+) s
+WHERE t.id = s.id;
+
+UPDATE tadgh_blog.categories c
+SET slug = s.slug
+FROM (
+    SELECT id,
+           CASE WHEN ROW_NUMBER() OVER (PARTITION BY base ORDER BY id) = 1
+                THEN base ELSE base || '-' || id END AS slug
+    FROM (
+        SELECT id, COALESCE(NULLIF(tadgh_blog.slugify(name), ''), 'category') AS base
+        FROM tadgh_blog.categories
+    ) b
+) s
+WHERE c.id = s.id;
+
+ALTER TABLE tadgh_blog.blog_posts ALTER COLUMN slug SET NOT NULL, ADD CONSTRAINT blog_posts_slug_key UNIQUE (slug);
+ALTER TABLE tadgh_blog.projects ALTER COLUMN slug SET NOT NULL, ADD CONSTRAINT projects_slug_key UNIQUE (slug);
+ALTER TABLE tadgh_blog.tags ALTER COLUMN slug SET NOT NULL, ADD CONSTRAINT tags_slug_key UNIQUE (slug);
+ALTER TABLE tadgh_blog.categories ALTER COLUMN slug SET NOT NULL, ADD CONSTRAINT categories_slug_key UNIQUE (slug);
+
+CREATE TABLE tadgh_blog.redirects (
+    id SERIAL PRIMARY KEY,
+    from_path TEXT NOT NULL UNIQUE,
+    to_path TEXT NOT NULL,
+    status_code INTEGER NOT NULL DEFAULT 301 CHECK (status_code IN (301, 302, 307, 308)),
+    -- Set for redirects recorded automatically when a slug or file name changes
+    entity_type TEXT CHECK (entity_type IN ('blog_post', 'project', 'tag', 'category')),
+    entity_id INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (from_path <> to_path)
+);
@@ -0,0 +1,71 @@
+-- One row per searchable entity. Titles and descriptions are kept in sync by the
+-- trigger below, markdown bodies are written by the app since they live on disk.
+CREATE TABLE tadgh_blog.search_documents (
+    entity_type TEXT NOT NULL CHECK (entity_type IN ('blog_post', 'project', 'tag', 'category')),
+    entity_id INTEGER NOT NULL,
+    title TEXT NOT NULL DEFAULT '',
+    content TEXT,
+    body TEXT,
+    document TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+        setweight(to_tsvector('english', coalesce(content, '')), 'B') ||
+        setweight(to_tsvector('english', coalesce(body, '')), 'C')
+    ) STORED,
+    PRIMARY KEY (entity_type, entity_id)
+);
+
+CREATE INDEX search_documents_document_idx
+    ON tadgh_blog.search_documents USING GIN (document);
+
+CREATE FUNCTION tadgh_blog.sync_search_document() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+DECLARE
+    kind TEXT := CASE TG_TABLE_NAME
+        WHEN 'blog_posts' THEN 'blog_post'
+        WHEN 'projects' THEN 'project'
+        WHEN 'tags' THEN 'tag'
+        ELSE 'category'
+    END;
+    doc JSONB;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        DELETE FROM tadgh_blog.search_documents
+        WHERE entity_type = kind AND entity_id = OLD.id;
+        RETURN OLD;
+    END IF;
+
+    -- Tags and categories have a name instead of a title
+    doc := to_jsonb(NEW);
+    INSERT INTO tadgh_blog.search_documents (entity_type, entity_id, title, content)
+    VALUES (kind, NEW.id, COALESCE(doc->>'title', doc->>'name', ''), doc->>'description')
+    ON CONFLICT (entity_type, entity_id) DO UPDATE
+    SET title = EXCLUDED.title,
+        content = EXCLUDED.content;
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER blog_posts_search_sync
+    AFTER INSERT OR UPDATE OR DELETE ON tadgh_blog.blog_posts
+    FOR EACH ROW EXECUTE FUNCTION tadgh_blog.sync_search_document();
+
+CREATE TRIGGER projects_search_sync
+    AFTER INSERT OR UPDATE OR DELETE ON tadgh_blog.projects
+    FOR EACH ROW EXECUTE FUNCTION tadgh_blog.sync_search_document();
+
+CREATE TRIGGER tags_search_sync
+    AFTER INSERT OR UPDATE OR DELETE ON tadgh_blog.tags
+    FOR EACH ROW EXECUTE FUNCTION tadgh_blog.sync_search_document();
+
+CREATE TRIGGER categories_search_sync
+    AFTER INSERT OR UPDATE OR DELETE ON tadgh_blog.categories
+    FOR EACH ROW EXECUTE FUNCTION tadgh_blog.sync_search_document();
+
+INSERT INTO tadgh_blog.search_documents (entity_type, entity_id, title, content)
+SELECT 'blog_post', id, title, description FROM tadgh_blog.blog_posts
+UNION ALL
+SELECT 'project', id, title, description FROM tadgh_blog.projects
+UNION ALL
+SELECT 'tag', id, name, NULL FROM tadgh_blog.tags
+UNION ALL
+SELECT 'category', id, name, description FROM tadgh_blog.categories;
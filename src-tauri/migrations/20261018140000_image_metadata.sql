@@ -0,0 +1,12 @@
+-- Details for files in the images folder, keyed by file name like blog_posts.image_name
+CREATE TABLE tadgh_blog.images (
+    file_name TEXT PRIMARY KEY,
+    alt_text TEXT,
+    caption TEXT,
+    credit TEXT,
+    license TEXT,
+    -- Point to keep in view when cropping, as fractions of the width and height
+    focal_x REAL CHECK (focal_x BETWEEN 0 AND 1),
+    focal_y REAL CHECK (focal_y BETWEEN 0 AND 1),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,45 @@
+CREATE TABLE tadgh_blog.series (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- A post belongs to at most one series. Positions start at 1 and are kept contiguous by
+-- the trigger below; the uniqueness check is deferred so a reorder can shuffle them
+-- within a transaction.
+CREATE TABLE tadgh_blog.series_posts (
+    series_id INTEGER NOT NULL REFERENCES tadgh_blog.series (id) ON DELETE CASCADE,
+    blog_post_id INTEGER NOT NULL UNIQUE REFERENCES tadgh_blog.blog_posts (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL CHECK (position > 0),
+    PRIMARY KEY (series_id, blog_post_id),
+    CONSTRAINT series_posts_position_key UNIQUE (series_id, position) DEFERRABLE INITIALLY DEFERRED
+);
+
+-- Closes the gaps left by any delete, including posts deleted outright or by cascade
+CREATE FUNCTION tadgh_blog.compact_series_positions() RETURNS TRIGGER
+LANGUAGE plpgsql AS $$
+BEGIN
+    UPDATE tadgh_blog.series_posts sp
+    SET position = o.new_position
+    FROM (
+        SELECT blog_post_id,
+            ROW_NUMBER() OVER (PARTITION BY series_id ORDER BY position)::int AS new_position
+        FROM tadgh_blog.series_posts
+        WHERE series_id IN (SELECT series_id FROM removed)
+    ) o
+    WHERE sp.blog_post_id = o.blog_post_id AND sp.position <> o.new_position;
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER series_posts_compact
+    AFTER DELETE ON tadgh_blog.series_posts
+    REFERENCING OLD TABLE AS removed
+    FOR EACH STATEMENT EXECUTE FUNCTION tadgh_blog.compact_series_positions();
+
+ALTER TABLE tadgh_blog.redirects
+    DROP CONSTRAINT redirects_entity_type_check,
+    ADD CONSTRAINT redirects_entity_type_check
+        CHECK (entity_type IN ('blog_post', 'project', 'tag', 'category', 'series'));
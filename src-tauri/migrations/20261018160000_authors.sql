@@ -0,0 +1,35 @@
+CREATE TABLE tadgh_blog.authors (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    bio TEXT,
+    avatar_name TEXT, -- File in the blog images folder
+    links JSONB NOT NULL DEFAULT '[]', -- [{ "label": "...", "url": "..." }]
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Authors are credited in `position` order, starting at 1; the uniqueness check is
+-- deferred so positions can be renumbered within a transaction
+CREATE TABLE tadgh_blog.blog_post_authors (
+    blog_post_id INTEGER NOT NULL REFERENCES tadgh_blog.blog_posts (id) ON DELETE CASCADE,
+    author_id INTEGER NOT NULL REFERENCES tadgh_blog.authors (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL CHECK (position > 0),
+    PRIMARY KEY (blog_post_id, author_id),
+    CONSTRAINT blog_post_authors_position_key UNIQUE (blog_post_id, position) DEFERRABLE INITIALLY DEFERRED
+);
+
+CREATE TABLE tadgh_blog.project_authors (
+    project_id INTEGER NOT NULL REFERENCES tadgh_blog.projects (id) ON DELETE CASCADE,
+    author_id INTEGER NOT NULL REFERENCES tadgh_blog.authors (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL CHECK (position > 0),
+    PRIMARY KEY (project_id, author_id),
+    CONSTRAINT project_authors_position_key UNIQUE (project_id, position) DEFERRABLE INITIALLY DEFERRED
+);
+
+CREATE INDEX blog_post_authors_author_id_idx ON tadgh_blog.blog_post_authors (author_id);
+CREATE INDEX project_authors_author_id_idx ON tadgh_blog.project_authors (author_id);
+
+ALTER TABLE tadgh_blog.redirects
+    DROP CONSTRAINT redirects_entity_type_check,
+    ADD CONSTRAINT redirects_entity_type_check
+        CHECK (entity_type IN ('blog_post', 'project', 'tag', 'category', 'series', 'author'));
@@ -0,0 +1,15 @@
+-- Overrides for search engines and link previews. Left NULL, the site falls back to
+-- the title, description, permalink and hero image.
+ALTER TABLE tadgh_blog.blog_posts
+    ADD COLUMN meta_title TEXT,
+    ADD COLUMN meta_description TEXT,
+    ADD COLUMN canonical_url TEXT,
+    ADD COLUMN og_image TEXT, -- File in the blog images folder
+    ADD COLUMN noindex BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE tadgh_blog.projects
+    ADD COLUMN meta_title TEXT,
+    ADD COLUMN meta_description TEXT,
+    ADD COLUMN canonical_url TEXT,
+    ADD COLUMN og_image TEXT,
+    ADD COLUMN noindex BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,55 @@
+-- Reader comments are written by the site into its own tadgh_blog.comments table and
+-- moderated from the admin app. The site's table is expected to have at least:
+--
+--   id            SERIAL PRIMARY KEY
+--   blog_post_id  INT NOT NULL, referencing tadgh_blog.blog_posts(id)
+--   parent_id     INT, the comment being replied to
+--   author_name   TEXT NOT NULL
+--   author_email  TEXT
+--   author_url    TEXT
+--   ip_address    TEXT
+--   body          TEXT NOT NULL
+--   created_at    TIMESTAMPTZ NOT NULL
+--
+-- The table is only created here for databases the site hasn't set up yet. The
+-- moderation columns below are added alongside the site's own; comments that were
+-- already there were live before moderation existed, so they start out approved while
+-- new ones start out pending. Replies from the admin app are stored approved with
+-- is_admin_reply set.
+CREATE TABLE IF NOT EXISTS tadgh_blog.comments (
+    id SERIAL PRIMARY KEY,
+    blog_post_id INT NOT NULL REFERENCES tadgh_blog.blog_posts(id) ON DELETE CASCADE,
+    parent_id INT REFERENCES tadgh_blog.comments(id) ON DELETE CASCADE,
+    author_name TEXT NOT NULL,
+    author_email TEXT,
+    author_url TEXT,
+    ip_address TEXT,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE tadgh_blog.comments
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'approved'
+        CHECK (status IN ('pending', 'approved', 'rejected', 'spam')),
+    ADD COLUMN IF NOT EXISTS is_admin_reply BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS spam_score INT NOT NULL DEFAULT 0, -- 0 to 100
+    ADD COLUMN IF NOT EXISTS spam_reasons TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS scored_at TIMESTAMPTZ, -- NULL until the spam heuristics have run
+    ADD COLUMN IF NOT EXISTS moderated_at TIMESTAMPTZ;
+
+-- The 'approved' default above only fills in the existing rows
+ALTER TABLE tadgh_blog.comments ALTER COLUMN status SET DEFAULT 'pending';
+
+CREATE INDEX IF NOT EXISTS comments_blog_post_id_idx ON tadgh_blog.comments (blog_post_id);
+CREATE INDEX IF NOT EXISTS comments_status_idx ON tadgh_blog.comments (status, created_at DESC);
+CREATE INDEX IF NOT EXISTS comments_parent_id_idx ON tadgh_blog.comments (parent_id);
+
+-- Terms that count towards a comment's spam score, matched case-insensitively
+CREATE TABLE tadgh_blog.comment_blocklist (
+    id SERIAL PRIMARY KEY,
+    term TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Spam scoring looks up recent views by IP
+CREATE INDEX IF NOT EXISTS blog_post_ips_ip_address_idx ON tadgh_blog.blog_post_ips (ip_address, viewed_at);
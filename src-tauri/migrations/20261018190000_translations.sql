@@ -0,0 +1,25 @@
+-- Language of the post itself; translations are linked to it as localized variants
+ALTER TABLE tadgh_blog.blog_posts ADD COLUMN locale TEXT NOT NULL DEFAULT 'en';
+
+-- A translated variant of a post, served under /<locale>/blog/<slug>. Tags, authors
+-- and the hero image are shared with the source post.
+CREATE TABLE tadgh_blog.blog_post_translations (
+    id SERIAL PRIMARY KEY,
+    blog_post_id INT NOT NULL REFERENCES tadgh_blog.blog_posts(id) ON DELETE CASCADE,
+    locale TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    file_name TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    published BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (blog_post_id, locale)
+);
+
+CREATE INDEX blog_post_translations_locale_idx ON tadgh_blog.blog_post_translations (locale);
+
+ALTER TABLE tadgh_blog.redirects
+    DROP CONSTRAINT redirects_entity_type_check,
+    ADD CONSTRAINT redirects_entity_type_check
+        CHECK (entity_type IN ('blog_post', 'project', 'tag', 'category', 'series', 'author', 'translation'));
@@ -0,0 +1,451 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::HashMap;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::slugs::{change_slug, resolve_slug};
+use crate::validation::{Validate, Validator};
+use crate::AppState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorLink {
+    pub label: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Author {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub bio: Option<String>,
+    pub avatar_name: Option<String>, // File in the blog images folder
+    pub links: Vec<AuthorLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveAuthor {
+    pub name: String,
+    pub bio: Option<String>,
+    pub avatar_name: Option<String>,
+    pub links: Option<Vec<AuthorLink>>,
+    pub slug: Option<String>, // Generated from the name on create, kept on update when left out
+}
+
+impl Validate for SaveAuthor {
+    fn rules(&self, v: &mut Validator) {
+        v.required("name", &self.name)
+            .max_length("name", Some(&self.name), 100)
+            .max_length("bio", self.bio.as_deref(), 2000)
+            .file_name("avatar_name", self.avatar_name.as_deref())
+            .slug("slug", self.slug.as_deref());
+        for link in self.links.iter().flatten() {
+            v.required("links", &link.label)
+                .max_length("links", Some(&link.label), 50)
+                .required("links", &link.url)
+                .url("links", Some(&link.url));
+        }
+    }
+}
+
+// Links are stored as JSONB, read back as text since sqlx is built without json support
+struct AuthorRow {
+    id: i32,
+    name: String,
+    slug: String,
+    bio: Option<String>,
+    avatar_name: Option<String>,
+    links: String,
+}
+
+impl From<AuthorRow> for Author {
+    fn from(row: AuthorRow) -> Self {
+        Author {
+            id: row.id,
+            name: row.name,
+            slug: row.slug,
+            bio: row.bio,
+            avatar_name: row.avatar_name,
+            links: serde_json::from_str(&row.links).unwrap_or_default(),
+        }
+    }
+}
+
+fn links_json(links: &Option<Vec<AuthorLink>>) -> Result<String, String> {
+    serde_json::to_string(links.as_deref().unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+pub async fn get_blog_post_authors_in(
+    conn: &mut PgConnection,
+    blog_post_id: i32,
+) -> Result<Vec<Author>, String> {
+    let rows = sqlx::query_as!(
+        AuthorRow,
+        r#"
+        SELECT a.id, a.name, a.slug, a.bio, a.avatar_name, a.links::text as "links!"
+        FROM tadgh_blog.authors a
+        JOIN tadgh_blog.blog_post_authors bpa ON bpa.author_id = a.id
+        WHERE bpa.blog_post_id = $1
+        ORDER BY bpa.position ASC
+        "#,
+        blog_post_id
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().map(Author::from).collect())
+}
+
+pub async fn get_project_authors_in(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> Result<Vec<Author>, String> {
+    let rows = sqlx::query_as!(
+        AuthorRow,
+        r#"
+        SELECT a.id, a.name, a.slug, a.bio, a.avatar_name, a.links::text as "links!"
+        FROM tadgh_blog.authors a
+        JOIN tadgh_blog.project_authors pa ON pa.author_id = a.id
+        WHERE pa.project_id = $1
+        ORDER BY pa.position ASC
+        "#,
+        project_id
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().map(Author::from).collect())
+}
+
+// Every post's authors in credit order, keyed by post id, for feeds and exports
+pub async fn get_all_blog_post_authors(
+    conn: &mut PgConnection,
+) -> Result<HashMap<i32, Vec<Author>>, String> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT bpa.blog_post_id, a.id, a.name, a.slug, a.bio, a.avatar_name,
+            a.links::text as "links!"
+        FROM tadgh_blog.blog_post_authors bpa
+        JOIN tadgh_blog.authors a ON a.id = bpa.author_id
+        ORDER BY bpa.blog_post_id, bpa.position
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut authors: HashMap<i32, Vec<Author>> = HashMap::new();
+    for row in rows {
+        authors
+            .entry(row.blog_post_id)
+            .or_default()
+            .push(Author::from(AuthorRow {
+                id: row.id,
+                name: row.name,
+                slug: row.slug,
+                bio: row.bio,
+                avatar_name: row.avatar_name,
+                links: row.links,
+            }));
+    }
+    Ok(authors)
+}
+
+pub async fn get_all_project_authors(
+    conn: &mut PgConnection,
+) -> Result<HashMap<i32, Vec<Author>>, String> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT pa.project_id, a.id, a.name, a.slug, a.bio, a.avatar_name,
+            a.links::text as "links!"
+        FROM tadgh_blog.project_authors pa
+        JOIN tadgh_blog.authors a ON a.id = pa.author_id
+        ORDER BY pa.project_id, pa.position
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut authors: HashMap<i32, Vec<Author>> = HashMap::new();
+    for row in rows {
+        authors
+            .entry(row.project_id)
+            .or_default()
+            .push(Author::from(AuthorRow {
+                id: row.id,
+                name: row.name,
+                slug: row.slug,
+                bio: row.bio,
+                avatar_name: row.avatar_name,
+                links: row.links,
+            }));
+    }
+    Ok(authors)
+}
+
+// Repeated ids are dropped, keeping the first occurrence's place in the order
+fn dedupe(author_ids: Vec<i32>) -> Vec<i32> {
+    let mut unique = Vec::with_capacity(author_ids.len());
+    for id in author_ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    unique
+}
+
+#[tauri::command]
+pub async fn get_authors(state: State<'_, Mutex<AppState>>) -> Result<Vec<Author>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    let rows = sqlx::query_as!(
+        AuthorRow,
+        r#"
+        SELECT id, name, slug, bio, avatar_name, links::text as "links!"
+        FROM tadgh_blog.authors
+        ORDER BY name ASC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().map(Author::from).collect())
+}
+
+#[tauri::command]
+pub async fn create_author(
+    state: State<'_, Mutex<AppState>>,
+    author: SaveAuthor,
+) -> Result<Author, String> {
+    author.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let slug = resolve_slug(
+        &mut tx,
+        "author",
+        author.slug.as_deref(),
+        &author.name,
+        None,
+    )
+    .await?;
+    let links = links_json(&author.links)?;
+
+    let row = sqlx::query_as!(
+        AuthorRow,
+        r#"
+        INSERT INTO tadgh_blog.authors (name, slug, bio, avatar_name, links)
+        VALUES ($1, $2, $3, $4, $5::text::jsonb)
+        RETURNING id, name, slug, bio, avatar_name, links::text as "links!"
+        "#,
+        author.name,
+        slug,
+        author.bio,
+        author.avatar_name,
+        links
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(Author::from(row))
+}
+
+#[tauri::command]
+pub async fn update_author(
+    state: State<'_, Mutex<AppState>>,
+    id: i32,
+    author: SaveAuthor,
+) -> Result<(), String> {
+    author.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let links = links_json(&author.links)?;
+    let updated = sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.authors
+        SET name = $1, bio = $2, avatar_name = $3, links = $4::text::jsonb
+        WHERE id = $5
+        "#,
+        author.name,
+        author.bio,
+        author.avatar_name,
+        links,
+        id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if updated.rows_affected() == 0 {
+        return Err("Author not found".to_string());
+    }
+
+    if let Some(slug) = author.slug.as_deref().filter(|s| !s.trim().is_empty()) {
+        change_slug(&mut tx, "author", id, slug).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Posts and projects keep their other authors
+#[tauri::command]
+pub async fn delete_author(state: State<'_, Mutex<AppState>>, id: i32) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query!("DELETE FROM tadgh_blog.authors WHERE id = $1", id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Close the gaps left in the credit order
+    sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.blog_post_authors bpa
+        SET position = o.new_position
+        FROM (
+            SELECT blog_post_id, author_id,
+                ROW_NUMBER() OVER (PARTITION BY blog_post_id ORDER BY position)::int as new_position
+            FROM tadgh_blog.blog_post_authors
+        ) o
+        WHERE bpa.blog_post_id = o.blog_post_id AND bpa.author_id = o.author_id
+          AND bpa.position <> o.new_position
+        "#
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.project_authors pa
+        SET position = o.new_position
+        FROM (
+            SELECT project_id, author_id,
+                ROW_NUMBER() OVER (PARTITION BY project_id ORDER BY position)::int as new_position
+            FROM tadgh_blog.project_authors
+        ) o
+        WHERE pa.project_id = o.project_id AND pa.author_id = o.author_id
+          AND pa.position <> o.new_position
+        "#
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_blog_post_authors(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+) -> Result<Vec<Author>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    get_blog_post_authors_in(&mut conn, blog_post_id).await
+}
+
+// Replaces the post's authors, crediting them in the order given
+#[tauri::command]
+pub async fn set_blog_post_authors(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+    author_ids: Vec<i32>,
+) -> Result<Vec<Author>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.blog_post_authors WHERE blog_post_id = $1",
+        blog_post_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tadgh_blog.blog_post_authors (blog_post_id, author_id, position)
+        SELECT $1, ids.author_id, ids.position::int
+        FROM UNNEST($2::int[]) WITH ORDINALITY AS ids(author_id, position)
+        "#,
+        blog_post_id,
+        &dedupe(author_ids)
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let authors = get_blog_post_authors_in(&mut tx, blog_post_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(authors)
+}
+
+#[tauri::command]
+pub async fn get_project_authors(
+    state: State<'_, Mutex<AppState>>,
+    project_id: i32,
+) -> Result<Vec<Author>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    get_project_authors_in(&mut conn, project_id).await
+}
+
+#[tauri::command]
+pub async fn set_project_authors(
+    state: State<'_, Mutex<AppState>>,
+    project_id: i32,
+    author_ids: Vec<i32>,
+) -> Result<Vec<Author>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.project_authors WHERE project_id = $1",
+        project_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tadgh_blog.project_authors (project_id, author_id, position)
+        SELECT $1, ids.author_id, ids.position::int
+        FROM UNNEST($2::int[]) WITH ORDINALITY AS ids(author_id, position)
+        "#,
+        project_id,
+        &dedupe(author_ids)
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let authors = get_project_authors_in(&mut tx, project_id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(authors)
+}
@@ -1,8 +1,22 @@
-use chrono::NaiveDate;
+use chrono::{NaiveDate, Utc};
+use comrak::nodes::NodeValue;
+use comrak::{parse_document, Arena};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use sqlx::PgConnection;
+use std::path::Path;
+use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
+use crate::images::{copy_image, copy_image_metadata, remove_image_copy};
+use crate::listing::{LinkTable, ListOptions, Listing, Page};
+use crate::preview::{markdown_options, node_text};
+use crate::revisions::record_blog_post_revision;
+use crate::search::index_blog_body;
+use crate::settings::load_current_profile;
+use crate::slugs::{file_permalink, permalink, record_redirect, resolve_slug};
+use crate::tags::{replace_blog_post_tags, resolve_tag_selection, TagSelection};
+use crate::validation::{Validate, Validator};
+use crate::watcher::FolderWatcher;
 use crate::AppState;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -13,6 +27,8 @@ pub struct BlogPost {
     description: String,
     image_name: Option<String>,
     file_name: String,
+    published: Option<bool>, // Left out on update to keep the current status
+    slug: Option<String>,    // Left out on update to keep the current slug
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -22,62 +38,196 @@ pub struct CreateBlogPost {
     description: String,
     image_name: Option<String>,
     file_name: String,
+    published: Option<bool>, // Defaults to published, pass false to create a draft
+    slug: Option<String>,    // Generated from the title when not given
 }
-#[tauri::command]
-pub async fn get_blog_posts(state: State<'_, Mutex<AppState>>) -> Result<Vec<BlogPost>, String> {
-    let state = state.lock().await;
-    let pool = state.pool.as_ref().ok_or("Database not connected")?;
-    sqlx::query_as!(
-        BlogPost,
-        r#"
-        SELECT
-            id,
-            title,
-            created,
-            description,
-            image_name,
-            file_name
-        FROM tadgh_blog.blog_posts
-        ORDER BY created DESC
-        "#
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(|e| e.to_string())
+
+impl BlogPost {
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+}
+
+impl Validate for BlogPost {
+    fn rules(&self, v: &mut Validator) {
+        v.required("title", &self.title)
+            .max_length("title", Some(&self.title), 200)
+            .required("description", &self.description)
+            .max_length("description", Some(&self.description), 1000)
+            .date("created", self.created)
+            .required("file_name", &self.file_name)
+            .file_name("file_name", Some(&self.file_name))
+            .file_name("image_name", self.image_name.as_deref())
+            .slug("slug", self.slug.as_deref());
+    }
+}
+
+impl Validate for CreateBlogPost {
+    fn rules(&self, v: &mut Validator) {
+        v.required("title", &self.title)
+            .max_length("title", Some(&self.title), 200)
+            .required("description", &self.description)
+            .max_length("description", Some(&self.description), 1000)
+            .date("created", Some(self.created))
+            .required("file_name", &self.file_name)
+            .file_name("file_name", Some(&self.file_name))
+            .file_name("image_name", self.image_name.as_deref())
+            .slug("slug", self.slug.as_deref());
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DuplicateBlogPost {
+    title: Option<String>,       // Defaults to "<title> (copy)"
+    copy_markdown: Option<bool>, // On by default, otherwise the copy shares the markdown file
+    copy_image: Option<bool>,    // Otherwise the copy shares the hero image
+}
+
+// `my-post.md` becomes `my-post-copy.md`, then `my-post-copy-2.md` and so on
+pub fn copy_name(file_name: &str, attempt: u32) -> String {
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
+    let suffix = if attempt > 1 {
+        format!("-copy-{}", attempt)
+    } else {
+        "-copy".to_string()
+    };
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => format!("{}{}.{}", stem, suffix, extension),
+        None => format!("{}{}", stem, suffix),
+    }
+}
+
+// Reads a post's markdown from the current profile's blog folder, if it is configured and present
+pub async fn read_blog_markdown(app: &AppHandle, file_name: &str) -> Option<String> {
+    let folder = load_current_profile(app).await.ok()?.blog_folder()?;
+    tokio::fs::read_to_string(folder.join(file_name)).await.ok()
 }
 
+const BLOG_POST_LISTING: Listing = Listing {
+    source: r#"
+        SELECT id, title, created, description, image_name, file_name, published, slug
+        FROM tadgh_blog.blog_posts
+    "#,
+    date_column: Some("created"),
+    text_columns: &["title", "description", "file_name"],
+    tag_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_tags",
+        column: "blog_post_id",
+        owner: "id",
+    }),
+    author_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_authors",
+        column: "blog_post_id",
+        owner: "id",
+    }),
+    status_column: None,
+    sort_fields: &[("created", "date"), ("title", "text"), ("id", "int4")],
+    default_sort: ("created", true),
+};
+
 #[tauri::command]
-pub async fn create_blog_post(
+pub async fn get_blog_posts(
     state: State<'_, Mutex<AppState>>,
-    blog_post: CreateBlogPost,
-) -> Result<BlogPost, String> {
+    options: Option<ListOptions>,
+) -> Result<Page<BlogPost>, String> {
     let state = state.lock().await;
     let pool = state.pool.as_ref().ok_or("Database not connected")?;
-    sqlx::query_as!(
+    BLOG_POST_LISTING.fetch(pool, options, None).await
+}
+
+async fn insert_blog_post(
+    conn: &mut PgConnection,
+    blog_post: &CreateBlogPost,
+    body: Option<&str>,
+) -> Result<BlogPost, String> {
+    let slug = resolve_slug(
+        conn,
+        "blog_post",
+        blog_post.slug.as_deref(),
+        &blog_post.title,
+        None,
+    )
+    .await?;
+
+    let created = sqlx::query_as!(
         BlogPost,
         r#"
-        INSERT INTO tadgh_blog.blog_posts (title, created, description, image_name, file_name)
-        VALUES ($1, $2, $3, $4, $5)
-        RETURNING id, title, created, description, image_name, file_name
+        INSERT INTO tadgh_blog.blog_posts (title, created, description, image_name, file_name, published, slug)
+        VALUES ($1, $2, $3, $4, $5, COALESCE($6, TRUE), $7)
+        RETURNING id, title, created, description, image_name, file_name, published as "published?", slug
         "#,
         blog_post.title,
         blog_post.created,
         blog_post.description,
         blog_post.image_name,
-        blog_post.file_name
+        blog_post.file_name,
+        blog_post.published,
+        slug
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    if let Some(body) = body {
+        index_blog_body(conn, created.id, Some(body)).await?;
+    }
+
+    Ok(created)
 }
 
-#[tauri::command]
-pub async fn update_blog_post(
-    state: State<'_, Mutex<AppState>>,
-    blog_post: BlogPost,
+async fn apply_blog_post_update(
+    conn: &mut PgConnection,
+    blog_post: &BlogPost,
+    blog_folder: Option<&Path>,
 ) -> Result<(), String> {
-    let state = state.lock().await;
-    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let previous = sqlx::query!(
+        "SELECT slug, file_name FROM tadgh_blog.blog_posts WHERE id = $1 FOR UPDATE",
+        blog_post.id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Blog post not found")?;
+
+    let slug = match blog_post.slug.as_deref() {
+        Some(requested) if requested != previous.slug => {
+            resolve_slug(
+                conn,
+                "blog_post",
+                Some(requested),
+                &blog_post.title,
+                Some(blog_post.id),
+            )
+            .await?
+        }
+        _ => previous.slug.clone(),
+    };
+
+    // The baseline is the post as it was, so its body comes from the file it pointed at
+    let read_body = |file_name: &str| {
+        let path = blog_folder.map(|folder| folder.join(file_name));
+        async move {
+            match path {
+                Some(path) => tokio::fs::read_to_string(path).await.ok(),
+                None => None,
+            }
+        }
+    };
+    let previous_body = read_body(&previous.file_name).await;
+    let body = if blog_post.file_name == previous.file_name {
+        previous_body.clone()
+    } else {
+        read_body(&blog_post.file_name).await
+    };
+    let body = body.as_deref();
+
+    // Keep the pre-edit state around the first time a post is changed
+    record_blog_post_revision(conn, blog_post.id, previous_body.as_deref(), true).await?;
+
     sqlx::query!(
         r#"
         UPDATE tadgh_blog.blog_posts
@@ -85,22 +235,409 @@ pub async fn update_blog_post(
             created = $2,
             description = $3,
             image_name = $4,
-            file_name = $5
-        WHERE id = $6
+            file_name = $5,
+            slug = $6,
+            published = COALESCE($7, published)
+        WHERE id = $8
         "#,
         blog_post.title,
         blog_post.created,
         blog_post.description,
         blog_post.image_name,
         blog_post.file_name,
+        slug,
+        blog_post.published,
         blog_post.id
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| e.to_string())?;
 
+    // Old links keep working whether they were built from the slug or the file name
+    let current_path = permalink("blog_post", &slug);
+    if slug != previous.slug {
+        let old_path = permalink("blog_post", &previous.slug);
+        record_redirect(conn, &old_path, &current_path, "blog_post", blog_post.id).await?;
+    }
+    if blog_post.file_name != previous.file_name {
+        let old_path = file_permalink(&previous.file_name);
+        record_redirect(conn, &old_path, &current_path, "blog_post", blog_post.id).await?;
+    }
+
+    record_blog_post_revision(conn, blog_post.id, body, false).await?;
+
+    if let Some(body) = body {
+        index_blog_body(conn, blog_post.id, Some(body)).await?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_blog_post(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post: CreateBlogPost,
+) -> Result<BlogPost, String> {
+    blog_post.validate()?;
+    let body = read_blog_markdown(&app, &blog_post.file_name).await;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let created = insert_blog_post(&mut tx, &blog_post, body.as_deref()).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+// Creates the post and attaches its tags in one transaction, creating tags given by
+// name that don't exist yet
+#[tauri::command]
+pub async fn create_blog_post_with_tags(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post: CreateBlogPost,
+    tags: TagSelection,
+) -> Result<BlogPost, String> {
+    blog_post.validate()?;
+    tags.validate()?;
+    let body = read_blog_markdown(&app, &blog_post.file_name).await;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let created = insert_blog_post(&mut tx, &blog_post, body.as_deref()).await?;
+    let tag_ids = resolve_tag_selection(&mut tx, &tags).await?;
+    replace_blog_post_tags(&mut tx, created.id, &tag_ids).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn update_blog_post(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post: BlogPost,
+) -> Result<(), String> {
+    blog_post.validate()?;
+    let blog_folder = load_current_profile(&app)
+        .await
+        .ok()
+        .and_then(|profile| profile.blog_folder());
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    apply_blog_post_update(&mut tx, &blog_post, blog_folder.as_deref()).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Updates the post and replaces its tag set in one transaction
+#[tauri::command]
+pub async fn update_blog_post_with_tags(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post: BlogPost,
+    tags: TagSelection,
+) -> Result<(), String> {
+    blog_post.validate()?;
+    tags.validate()?;
+    let blog_folder = load_current_profile(&app)
+        .await
+        .ok()
+        .and_then(|profile| profile.blog_folder());
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    apply_blog_post_update(&mut tx, &blog_post, blog_folder.as_deref()).await?;
+    let tag_ids = resolve_tag_selection(&mut tx, &tags).await?;
+    replace_blog_post_tags(&mut tx, blog_post.id, &tag_ids).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
     Ok(())
 }
+
+// Copies a post and its tags as a new draft dated today. Copied files are removed
+// again if the database work fails.
+#[tauri::command]
+pub async fn duplicate_blog_post(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    watcher: State<'_, FolderWatcher>,
+    blog_post_id: i32,
+    options: Option<DuplicateBlogPost>,
+) -> Result<BlogPost, String> {
+    let options = options.unwrap_or_default();
+    let profile = load_current_profile(&app).await.ok();
+    let blog_folder = profile.as_ref().and_then(|profile| profile.blog_folder());
+    let images_folder = profile
+        .as_ref()
+        .and_then(|profile| profile.blog_images_folder());
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    let original = sqlx::query_as!(
+        BlogPost,
+        r#"
+        SELECT id, title, created, description, image_name, file_name, published as "published?", slug
+        FROM tadgh_blog.blog_posts
+        WHERE id = $1
+        "#,
+        blog_post_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Blog post not found")?;
+
+    // Checked before any files are copied; long titles are shortened to make room for the suffix
+    let title = options
+        .title
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| {
+            let base: String = original.title.chars().take(200 - " (copy)".len()).collect();
+            format!("{} (copy)", base.trim_end())
+        });
+    Validator::default()
+        .max_length("title", Some(&title), 200)
+        .finish()?;
+
+    let copy_markdown = options.copy_markdown.unwrap_or(true);
+    let copy_hero = options.copy_image.unwrap_or(false)
+        && original
+            .image_name
+            .as_deref()
+            .is_some_and(|name| !name.is_empty());
+    if copy_markdown && blog_folder.is_none() {
+        return Err("Blog folder not configured".to_string());
+    }
+    if copy_hero && images_folder.is_none() {
+        return Err("Blog images folder not configured".to_string());
+    }
+
+    let mut file_name = original.file_name.clone();
+    let mut copied_markdown = None;
+    if let (true, Some(folder)) = (copy_markdown, &blog_folder) {
+        let mut attempt = 1;
+        file_name = copy_name(&original.file_name, attempt);
+        while folder.join(&file_name).exists() {
+            attempt += 1;
+            file_name = copy_name(&original.file_name, attempt);
+        }
+        // Keeps the watcher from creating a draft for the copy before it is committed
+        let writing = watcher.expect_write(&file_name);
+        tokio::fs::copy(folder.join(&original.file_name), folder.join(&file_name))
+            .await
+            .map_err(|e| e.to_string())?;
+        copied_markdown = Some((folder.join(&file_name), writing));
+    }
+
+    let mut image_name = original.image_name.clone();
+    let mut copied_image = None;
+    if let (true, Some(folder), Some(name)) = (copy_hero, &images_folder, &original.image_name) {
+        match copy_image(folder, name).await {
+            Ok(new_name) => {
+                image_name = Some(new_name.clone());
+                copied_image = Some(new_name);
+            }
+            Err(e) => {
+                if let Some((path, _)) = &copied_markdown {
+                    let _ = tokio::fs::remove_file(path).await;
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    let body = match &blog_folder {
+        Some(folder) => tokio::fs::read_to_string(folder.join(&file_name))
+            .await
+            .ok(),
+        None => None,
+    };
+    let result = async {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+        let slug = resolve_slug(&mut tx, "blog_post", None, &title, None).await?;
+
+        let created = sqlx::query_as!(
+            BlogPost,
+            r#"
+            INSERT INTO tadgh_blog.blog_posts (title, created, description, image_name, file_name, published, slug)
+            VALUES ($1, CURRENT_DATE, $2, $3, $4, FALSE, $5)
+            RETURNING id, title, created, description, image_name, file_name, published as "published?", slug
+            "#,
+            title,
+            original.description,
+            image_name,
+            file_name,
+            slug
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tadgh_blog.blog_post_tags (blog_post_id, tag_id)
+            SELECT $1, tag_id FROM tadgh_blog.blog_post_tags WHERE blog_post_id = $2
+            "#,
+            created.id,
+            original.id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tadgh_blog.blog_post_authors (blog_post_id, author_id, position)
+            SELECT $1, author_id, position FROM tadgh_blog.blog_post_authors WHERE blog_post_id = $2
+            "#,
+            created.id,
+            original.id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if let Some(new_name) = &copied_image {
+            copy_image_metadata(&mut tx, original.image_name.as_deref(), new_name).await?;
+        }
+
+        if let Some(body) = &body {
+            index_blog_body(&mut tx, created.id, Some(body)).await?;
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        Ok::<BlogPost, String>(created)
+    }
+    .await;
+
+    if result.is_err() {
+        if let Some((path, _)) = &copied_markdown {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        if let (Some(folder), Some(name)) = (&images_folder, &copied_image) {
+            remove_image_copy(folder, name).await;
+        }
+    }
+
+    result
+}
+
+// Title from the first heading, or the file name when there is none, and the
+// description from the first paragraph
+fn draft_from_markdown(file_name: &str, markdown: &str) -> CreateBlogPost {
+    let arena = Arena::new();
+    let root = parse_document(&arena, markdown, &markdown_options());
+    let mut heading = None;
+    let mut paragraph = None;
+    for node in root.descendants() {
+        match node.data().value {
+            NodeValue::Heading(_) if heading.is_none() => heading = Some(node_text(node)),
+            NodeValue::Paragraph if paragraph.is_none() => paragraph = Some(node_text(node)),
+            _ => {}
+        }
+    }
+
+    let stem = Path::new(file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
+    let title = heading
+        .map(|heading| heading.trim().to_string())
+        .filter(|heading| !heading.is_empty())
+        .unwrap_or_else(|| stem.replace(['-', '_'], " "));
+    let title: String = title.chars().take(200).collect();
+    let description = paragraph
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .map(|paragraph| paragraph.chars().take(1000).collect())
+        .unwrap_or_else(|| title.clone());
+
+    CreateBlogPost {
+        title,
+        created: Utc::now().date_naive(),
+        description,
+        image_name: None,
+        file_name: file_name.to_string(),
+        published: Some(false),
+        slug: None,
+    }
+}
+
+// Adds a draft post for a markdown file that has no post yet
+pub async fn insert_draft_from_file(
+    conn: &mut PgConnection,
+    file_name: &str,
+    markdown: &str,
+) -> Result<BlogPost, String> {
+    let tracked = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM tadgh_blog.blog_posts WHERE file_name = $1)
+            OR EXISTS (SELECT 1 FROM tadgh_blog.blog_post_translations WHERE file_name = $1)
+            as "tracked!"
+        "#,
+        file_name
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    if tracked {
+        return Err(format!(
+            "{} already has a blog post or translation",
+            file_name
+        ));
+    }
+
+    let blog_post = draft_from_markdown(file_name, markdown);
+    blog_post.validate()?;
+    insert_blog_post(conn, &blog_post, Some(markdown)).await
+}
+
+#[tauri::command]
+pub async fn create_blog_post_from_file(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    file_name: String,
+) -> Result<BlogPost, String> {
+    // Checked before the read so the name can't point outside the blog folder
+    Validator::default()
+        .required("file_name", &file_name)
+        .file_name("file_name", Some(&file_name))
+        .finish()?;
+
+    let markdown = read_blog_markdown(&app, &file_name)
+        .await
+        .ok_or_else(|| format!("Could not read {} from the blog folder", file_name))?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let created = insert_draft_from_file(&mut tx, &file_name, &markdown).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
 #[tauri::command]
 pub async fn delete_blog_post(
     state: State<'_, Mutex<AppState>>,
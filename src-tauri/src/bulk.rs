@@ -0,0 +1,647 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{Connection, PgConnection};
+use std::collections::HashMap;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::blog::read_blog_markdown;
+use crate::revisions::{record_blog_post_revision, record_project_revision};
+use crate::tags::{resolve_tag_selection, TagSelection};
+use crate::validation::{Validate, Validator};
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BulkOperation {
+    AddTags {
+        tags: TagSelection,
+    },
+    RemoveTags {
+        tag_ids: Vec<i32>,
+    },
+    // Categories come from tags, so this re-tags each item: every tag of the category
+    // is attached and tags that only belong to other categories are removed.
+    // Uncategorised tags are left alone. A dry run lists the tags each item ends up with.
+    SetCategory {
+        category_id: i32,
+    },
+    // `published` applies to posts, `released` and `live` to projects
+    SetStatus {
+        published: Option<bool>,
+        released: Option<bool>,
+        live: Option<bool>,
+    },
+    ShiftDates {
+        days: i32,
+    },
+    Delete,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkItemResult {
+    pub id: i32,
+    pub title: Option<String>,
+    pub outcome: String, // "changed", "unchanged", "not_found" or "failed"
+    pub changes: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkReport {
+    pub dry_run: bool,
+    pub committed: bool, // False for dry runs and whenever an item failed
+    pub changed: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    pub items: Vec<BulkItemResult>,
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    BlogPost,
+    Project,
+}
+
+async fn add_tags(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+    tag_ids: &[i32],
+) -> Result<Vec<String>, String> {
+    let names = match target {
+        Target::BlogPost => {
+            sqlx::query_scalar!(
+                r#"
+            WITH added AS (
+                INSERT INTO tadgh_blog.blog_post_tags (blog_post_id, tag_id)
+                SELECT $1, UNNEST($2::int[])
+                ON CONFLICT DO NOTHING
+                RETURNING tag_id
+            )
+            SELECT t.name FROM added JOIN tadgh_blog.tags t ON t.id = added.tag_id
+            ORDER BY t.name
+            "#,
+                id,
+                tag_ids
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+        Target::Project => {
+            sqlx::query_scalar!(
+                r#"
+            WITH added AS (
+                INSERT INTO tadgh_blog.project_tags (project_id, tag_id)
+                SELECT $1, UNNEST($2::int[])
+                ON CONFLICT DO NOTHING
+                RETURNING tag_id
+            )
+            SELECT t.name FROM added JOIN tadgh_blog.tags t ON t.id = added.tag_id
+            ORDER BY t.name
+            "#,
+                id,
+                tag_ids
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+    }
+    .map_err(|e| e.to_string())?;
+
+    Ok(names
+        .into_iter()
+        .map(|name| format!("Added tag {}", name))
+        .collect())
+}
+
+async fn remove_tags(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+    tag_ids: &[i32],
+) -> Result<Vec<String>, String> {
+    let names = match target {
+        Target::BlogPost => {
+            sqlx::query_scalar!(
+                r#"
+            WITH removed AS (
+                DELETE FROM tadgh_blog.blog_post_tags
+                WHERE blog_post_id = $1 AND tag_id = ANY($2)
+                RETURNING tag_id
+            )
+            SELECT t.name FROM removed JOIN tadgh_blog.tags t ON t.id = removed.tag_id
+            ORDER BY t.name
+            "#,
+                id,
+                tag_ids
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+        Target::Project => {
+            sqlx::query_scalar!(
+                r#"
+            WITH removed AS (
+                DELETE FROM tadgh_blog.project_tags
+                WHERE project_id = $1 AND tag_id = ANY($2)
+                RETURNING tag_id
+            )
+            SELECT t.name FROM removed JOIN tadgh_blog.tags t ON t.id = removed.tag_id
+            ORDER BY t.name
+            "#,
+                id,
+                tag_ids
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+    }
+    .map_err(|e| e.to_string())?;
+
+    Ok(names
+        .into_iter()
+        .map(|name| format!("Removed tag {}", name))
+        .collect())
+}
+
+async fn tag_names(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+) -> Result<Vec<String>, String> {
+    match target {
+        Target::BlogPost => {
+            sqlx::query_scalar!(
+                r#"
+            SELECT t.name FROM tadgh_blog.blog_post_tags bpt
+            JOIN tadgh_blog.tags t ON t.id = bpt.tag_id
+            WHERE bpt.blog_post_id = $1
+            ORDER BY t.name
+            "#,
+                id
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+        Target::Project => {
+            sqlx::query_scalar!(
+                r#"
+            SELECT t.name FROM tadgh_blog.project_tags pt
+            JOIN tadgh_blog.tags t ON t.id = pt.tag_id
+            WHERE pt.project_id = $1
+            ORDER BY t.name
+            "#,
+                id
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+// Current tags of the item that belong to a category other than `category_id` only
+async fn other_category_tags(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+    category_id: i32,
+) -> Result<Vec<i32>, String> {
+    match target {
+        Target::BlogPost => {
+            sqlx::query_scalar!(
+                r#"
+            SELECT DISTINCT bpt.tag_id as "tag_id!"
+            FROM tadgh_blog.blog_post_tags bpt
+            JOIN tadgh_blog.tag_categories tc ON tc.tag_id = bpt.tag_id
+            WHERE bpt.blog_post_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM tadgh_blog.tag_categories own
+                  WHERE own.tag_id = bpt.tag_id AND own.category_id = $2
+              )
+            "#,
+                id,
+                category_id
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+        Target::Project => {
+            sqlx::query_scalar!(
+                r#"
+            SELECT DISTINCT pt.tag_id as "tag_id!"
+            FROM tadgh_blog.project_tags pt
+            JOIN tadgh_blog.tag_categories tc ON tc.tag_id = pt.tag_id
+            WHERE pt.project_id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM tadgh_blog.tag_categories own
+                  WHERE own.tag_id = pt.tag_id AND own.category_id = $2
+              )
+            "#,
+                id,
+                category_id
+            )
+            .fetch_all(&mut *conn)
+            .await
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+async fn set_status(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+    published: Option<bool>,
+    released: Option<bool>,
+    live: Option<bool>,
+) -> Result<Vec<String>, String> {
+    let mut changes = Vec::new();
+    match target {
+        Target::BlogPost => {
+            let published = published.ok_or("Posts only have a published status")?;
+            let updated = sqlx::query!(
+                r#"
+                UPDATE tadgh_blog.blog_posts SET published = $2
+                WHERE id = $1 AND published <> $2
+                "#,
+                id,
+                published
+            )
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+            if updated.rows_affected() > 0 {
+                changes.push(
+                    if published {
+                        "Published"
+                    } else {
+                        "Unpublished"
+                    }
+                    .to_string(),
+                );
+            }
+        }
+        Target::Project => {
+            if released.is_none() && live.is_none() {
+                return Err("Projects have released and live statuses".to_string());
+            }
+            let previous = sqlx::query!(
+                r#"
+                UPDATE tadgh_blog.projects p
+                SET released = COALESCE($2, p.released), live = COALESCE($3, p.live)
+                FROM tadgh_blog.projects old
+                WHERE p.id = $1 AND old.id = p.id
+                RETURNING old.released, old.live
+                "#,
+                id,
+                released,
+                live
+            )
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+            if let Some(released) = released.filter(|r| *r != previous.released) {
+                changes.push(if released { "Released" } else { "Unreleased" }.to_string());
+            }
+            if let Some(live) = live.filter(|l| *l != previous.live) {
+                changes.push(
+                    if live {
+                        "Marked live"
+                    } else {
+                        "Marked not live"
+                    }
+                    .to_string(),
+                );
+            }
+        }
+    }
+    Ok(changes)
+}
+
+async fn shift_date(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+    days: i32,
+) -> Result<Vec<String>, String> {
+    if days == 0 {
+        return Ok(Vec::new());
+    }
+    let dates = match target {
+        Target::BlogPost => sqlx::query!(
+            r#"
+            UPDATE tadgh_blog.blog_posts p SET created = p.created + $2::int
+            FROM tadgh_blog.blog_posts old
+            WHERE p.id = $1 AND old.id = p.id
+            RETURNING old.created as "old_date", p.created as "new_date"
+            "#,
+            id,
+            days
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|row| (row.old_date, row.new_date)),
+        // Undated projects stay undated
+        Target::Project => sqlx::query!(
+            r#"
+            UPDATE tadgh_blog.projects p SET created = p.created + $2::int
+            FROM tadgh_blog.projects old
+            WHERE p.id = $1 AND old.id = p.id AND p.created IS NOT NULL
+            RETURNING old.created as "old_date!", p.created as "new_date!"
+            "#,
+            id,
+            days
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|row| (row.old_date, row.new_date)),
+    };
+
+    let Some((old_date, new_date)) = dates else {
+        return Ok(Vec::new());
+    };
+    // Held to the same range as dates entered in the forms
+    Validator::default()
+        .date("created", Some(new_date))
+        .finish()?;
+
+    Ok(vec![format!(
+        "Date moved from {} to {}",
+        old_date, new_date
+    )])
+}
+
+async fn delete(conn: &mut PgConnection, target: Target, id: i32) -> Result<Vec<String>, String> {
+    let deleted = match target {
+        Target::BlogPost => {
+            sqlx::query!("DELETE FROM tadgh_blog.blog_posts WHERE id = $1", id)
+                .execute(&mut *conn)
+                .await
+        }
+        Target::Project => {
+            sqlx::query!("DELETE FROM tadgh_blog.projects WHERE id = $1", id)
+                .execute(&mut *conn)
+                .await
+        }
+    }
+    .map_err(|e| e.to_string())?;
+
+    Ok(if deleted.rows_affected() > 0 {
+        vec!["Deleted".to_string()]
+    } else {
+        Vec::new()
+    })
+}
+
+async fn record_revision(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+    body: Option<&str>,
+    baseline_only: bool,
+) -> Result<(), String> {
+    match target {
+        Target::BlogPost => record_blog_post_revision(conn, id, body, baseline_only).await,
+        Target::Project => record_project_revision(conn, id, baseline_only).await,
+    }
+}
+
+// Status, date and delete changes keep revisions like the single-item edits do, so
+// they can be reviewed and restored afterwards
+async fn apply(
+    conn: &mut PgConnection,
+    target: Target,
+    id: i32,
+    operation: &BulkOperation,
+    tag_ids: &[i32],
+    body: Option<&str>,
+) -> Result<Vec<String>, String> {
+    match operation {
+        BulkOperation::AddTags { .. } => add_tags(conn, target, id, tag_ids).await,
+        BulkOperation::RemoveTags { tag_ids } => remove_tags(conn, target, id, tag_ids).await,
+        BulkOperation::SetCategory { category_id } => {
+            let stale = other_category_tags(conn, target, id, *category_id).await?;
+            let mut changes = remove_tags(conn, target, id, &stale).await?;
+            changes.extend(add_tags(conn, target, id, tag_ids).await?);
+            if !changes.is_empty() {
+                let tags = tag_names(conn, target, id).await?;
+                changes.push(format!("Tags are now {}", tags.join(", ")));
+            }
+            Ok(changes)
+        }
+        BulkOperation::SetStatus {
+            published,
+            released,
+            live,
+        } => {
+            record_revision(conn, target, id, body, true).await?;
+            let changes = set_status(conn, target, id, *published, *released, *live).await?;
+            record_revision(conn, target, id, body, false).await?;
+            Ok(changes)
+        }
+        BulkOperation::ShiftDates { days } => {
+            record_revision(conn, target, id, body, true).await?;
+            let changes = shift_date(conn, target, id, *days).await?;
+            record_revision(conn, target, id, body, false).await?;
+            Ok(changes)
+        }
+        BulkOperation::Delete => {
+            record_revision(conn, target, id, body, true).await?;
+            delete(conn, target, id).await
+        }
+    }
+}
+
+fn keeps_revisions(operation: &BulkOperation) -> bool {
+    matches!(
+        operation,
+        BulkOperation::SetStatus { .. } | BulkOperation::ShiftDates { .. } | BulkOperation::Delete
+    )
+}
+
+// Applies the operation to each id inside one transaction. Every item runs in its own
+// savepoint so one failure doesn't hide the results of the rest, but nothing is
+// committed unless all of them succeed. Dry runs always roll back.
+async fn run_bulk(
+    app: &AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    target: Target,
+    ids: Vec<i32>,
+    operation: BulkOperation,
+    dry_run: bool,
+) -> Result<BulkReport, String> {
+    if let BulkOperation::AddTags { tags } = &operation {
+        tags.validate()?;
+    }
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // Titles, and the markdown file of each post
+    let titles: HashMap<i32, (String, Option<String>)> = match target {
+        Target::BlogPost => sqlx::query!(
+            "SELECT id, title, file_name FROM tadgh_blog.blog_posts WHERE id = ANY($1)",
+            &ids
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| (row.id, (row.title, Some(row.file_name))))
+        .collect(),
+        Target::Project => sqlx::query!(
+            "SELECT id, title FROM tadgh_blog.projects WHERE id = ANY($1)",
+            &ids
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| (row.id, (row.title, None)))
+        .collect(),
+    };
+
+    // Tags to attach are worked out once for the whole batch
+    let tag_ids = match &operation {
+        BulkOperation::AddTags { tags } => resolve_tag_selection(&mut tx, tags).await?,
+        BulkOperation::SetCategory { category_id } => sqlx::query_scalar!(
+            "SELECT tag_id FROM tadgh_blog.tag_categories WHERE category_id = $1",
+            category_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?,
+        _ => Vec::new(),
+    };
+
+    let mut items = Vec::with_capacity(ids.len());
+    let mut seen = Vec::with_capacity(ids.len());
+    for id in ids {
+        if seen.contains(&id) {
+            continue;
+        }
+        seen.push(id);
+
+        let Some((title, file_name)) = titles.get(&id).cloned() else {
+            items.push(BulkItemResult {
+                id,
+                title: None,
+                outcome: "not_found".to_string(),
+                changes: Vec::new(),
+                error: None,
+            });
+            continue;
+        };
+
+        let body = match file_name {
+            Some(file_name) if keeps_revisions(&operation) => {
+                read_blog_markdown(app, &file_name).await
+            }
+            _ => None,
+        };
+
+        let mut savepoint = tx.begin().await.map_err(|e| e.to_string())?;
+        let result = apply(
+            &mut savepoint,
+            target,
+            id,
+            &operation,
+            &tag_ids,
+            body.as_deref(),
+        )
+        .await;
+        let item = match result {
+            Ok(changes) => {
+                savepoint.commit().await.map_err(|e| e.to_string())?;
+                BulkItemResult {
+                    id,
+                    title: Some(title),
+                    outcome: if changes.is_empty() {
+                        "unchanged"
+                    } else {
+                        "changed"
+                    }
+                    .to_string(),
+                    changes,
+                    error: None,
+                }
+            }
+            Err(e) => {
+                savepoint.rollback().await.map_err(|e| e.to_string())?;
+                BulkItemResult {
+                    id,
+                    title: Some(title),
+                    outcome: "failed".to_string(),
+                    changes: Vec::new(),
+                    error: Some(e),
+                }
+            }
+        };
+        items.push(item);
+    }
+
+    let count = |outcome: &str| items.iter().filter(|item| item.outcome == outcome).count();
+    let changed = count("changed");
+    let unchanged = count("unchanged");
+    let failed = count("failed");
+
+    let committed = !dry_run && failed == 0;
+    if committed {
+        tx.commit().await.map_err(|e| e.to_string())?;
+    } else {
+        tx.rollback().await.map_err(|e| e.to_string())?;
+    }
+
+    Ok(BulkReport {
+        dry_run,
+        committed,
+        changed,
+        unchanged,
+        failed,
+        items,
+    })
+}
+
+// Nothing is committed if any item fails. SetCategory replaces category tags, so run it
+// with `dry_run` first to see what each item is re-tagged to.
+#[tauri::command]
+pub async fn bulk_update_blog_posts(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    ids: Vec<i32>,
+    operation: BulkOperation,
+    dry_run: Option<bool>,
+) -> Result<BulkReport, String> {
+    run_bulk(
+        &app,
+        state,
+        Target::BlogPost,
+        ids,
+        operation,
+        dry_run.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn bulk_update_projects(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    ids: Vec<i32>,
+    operation: BulkOperation,
+    dry_run: Option<bool>,
+) -> Result<BulkReport, String> {
+    run_bulk(
+        &app,
+        state,
+        Target::Project,
+        ids,
+        operation,
+        dry_run.unwrap_or(false),
+    )
+    .await
+}
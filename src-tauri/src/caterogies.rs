@@ -1,3 +1,5 @@
+use crate::slugs::{change_slug, resolve_slug};
+use crate::validation::validate_named;
 use crate::{AppState, Tag};
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -8,6 +10,7 @@ pub struct Category {
     id: i32,
     name: String,
     description: Option<String>,
+    slug: String,
 }
 
 #[tauri::command]
@@ -17,7 +20,7 @@ pub async fn get_categories(state: State<'_, Mutex<AppState>>) -> Result<Vec<Cat
     sqlx::query_as!(
         Category,
         r#"
-        SELECT id, name, description
+        SELECT id, name, description, slug
         FROM tadgh_blog.categories
         ORDER BY name ASC
         "#
@@ -32,22 +35,33 @@ pub async fn create_category(
     state: State<'_, Mutex<AppState>>,
     name: String,
     description: Option<String>,
+    slug: Option<String>,
 ) -> Result<Category, String> {
+    validate_named(&name, description.as_deref(), slug.as_deref())?;
     let state = state.lock().await;
     let pool = state.pool.as_ref().ok_or("Database not connected")?;
-    sqlx::query_as!(
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let slug = resolve_slug(&mut tx, "category", slug.as_deref(), &name, None).await?;
+
+    let category = sqlx::query_as!(
         Category,
         r#"
-        INSERT INTO tadgh_blog.categories (name, description)
-        VALUES ($1, $2)
-        RETURNING id, name, description
+        INSERT INTO tadgh_blog.categories (name, description, slug)
+        VALUES ($1, $2, $3)
+        RETURNING id, name, description, slug
         "#,
         name,
-        description
+        description,
+        slug
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(category)
 }
 
 #[tauri::command]
@@ -122,9 +136,13 @@ pub async fn update_category(
     id: i32,
     name: String,
     description: Option<String>,
+    slug: Option<String>,
 ) -> Result<(), String> {
+    validate_named(&name, description.as_deref(), slug.as_deref())?;
     let state = state.lock().await;
     let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
     sqlx::query!(
         r#"
         UPDATE tadgh_blog.categories
@@ -135,10 +153,16 @@ pub async fn update_category(
         description,
         id
     )
-    .execute(pool)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
+    if let Some(slug) = slug {
+        change_slug(&mut tx, "category", id, &slug).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
@@ -194,7 +218,7 @@ pub async fn get_category_tags(
     sqlx::query_as!(
         Tag,
         r#"
-        SELECT t.id, t.name
+        SELECT t.id, t.name, t.slug
         FROM tadgh_blog.tags t
         JOIN tadgh_blog.tag_categories tc ON tc.tag_id = t.id
         WHERE tc.category_id = $1
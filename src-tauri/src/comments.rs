@@ -0,0 +1,552 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::listing::{LinkTable, ListOptions, Listing, Page};
+use crate::validation::{Validate, Validator};
+use crate::AppState;
+
+// Scores at or above this count as likely spam
+const SPAM_THRESHOLD: i32 = 50;
+// Views from one IP in the hour before commenting that look like a bot rather than a reader
+const ABUSIVE_VIEWS_PER_HOUR: i64 = 120;
+// Comments from one IP in the hour before that look like flooding
+const ABUSIVE_COMMENTS_PER_HOUR: i64 = 5;
+
+// A row of the site's comments table. Status, is_admin_reply, the spam fields and
+// moderated_at are the moderation columns the comments migration adds to it.
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Comment {
+    pub id: i32,
+    pub blog_post_id: i32,
+    pub blog_post_title: String,
+    pub parent_id: Option<i32>,
+    pub author_name: String,
+    pub author_email: Option<String>,
+    pub author_url: Option<String>,
+    pub ip_address: Option<String>,
+    pub body: String,
+    pub status: String, // "pending", "approved", "rejected" or "spam"
+    pub is_admin_reply: bool,
+    pub spam_score: i32,
+    pub spam_reasons: Vec<String>,
+    pub scored_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub moderated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentReply {
+    pub author_name: String,
+    pub body: String,
+}
+
+impl Validate for CommentReply {
+    fn rules(&self, v: &mut Validator) {
+        v.required("author_name", &self.author_name)
+            .max_length("author_name", Some(&self.author_name), 100)
+            .required("body", &self.body)
+            .max_length("body", Some(&self.body), 10000);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostCommentCounts {
+    pub blog_post_id: i32,
+    pub title: String,
+    pub total: i64,
+    pub pending: i64,
+    pub approved: i64,
+    pub rejected: i64,
+    pub spam: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpamScoreReport {
+    pub scored: usize,
+    pub likely_spam: usize, // At or above the threshold
+    pub marked_spam: usize, // Moved from pending to spam
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BlocklistTerm {
+    pub id: i32,
+    pub term: String,
+    pub created_at: DateTime<Utc>,
+}
+
+const COMMENT_LISTING: Listing = Listing {
+    source: r#"
+        SELECT
+            c.id,
+            c.blog_post_id,
+            bp.title as blog_post_title,
+            c.parent_id,
+            c.author_name,
+            c.author_email,
+            c.author_url,
+            c.ip_address,
+            c.body,
+            c.status,
+            c.is_admin_reply,
+            c.spam_score,
+            c.spam_reasons,
+            c.scored_at,
+            c.created_at,
+            c.moderated_at
+        FROM tadgh_blog.comments c
+        JOIN tadgh_blog.blog_posts bp ON bp.id = c.blog_post_id
+    "#,
+    date_column: Some("created_at"),
+    text_columns: &["author_name", "author_email", "ip_address", "body"],
+    tag_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_tags",
+        column: "blog_post_id",
+        owner: "blog_post_id",
+    }),
+    author_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_authors",
+        column: "blog_post_id",
+        owner: "blog_post_id",
+    }),
+    status_column: Some("status"),
+    sort_fields: &[
+        ("created_at", "timestamptz"),
+        ("spam_score", "int4"),
+        ("id", "int4"),
+    ],
+    default_sort: ("created_at", true),
+};
+
+async fn fetch_comment(conn: &mut PgConnection, id: i32) -> Result<Comment, String> {
+    sqlx::query_as!(
+        Comment,
+        r#"
+        SELECT
+            c.id,
+            c.blog_post_id,
+            bp.title as blog_post_title,
+            c.parent_id,
+            c.author_name,
+            c.author_email,
+            c.author_url,
+            c.ip_address,
+            c.body,
+            c.status,
+            c.is_admin_reply,
+            c.spam_score,
+            c.spam_reasons,
+            c.scored_at,
+            c.created_at,
+            c.moderated_at
+        FROM tadgh_blog.comments c
+        JOIN tadgh_blog.blog_posts bp ON bp.id = c.blog_post_id
+        WHERE c.id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Comment not found".to_string())
+}
+
+// Comments of one post, or of every post, newest first. Filter with `statuses`.
+#[tauri::command]
+pub async fn get_comments(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: Option<i32>,
+    options: Option<ListOptions>,
+) -> Result<Page<Comment>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    COMMENT_LISTING
+        .fetch(pool, options, blog_post_id.map(|id| ("blog_post_id", id)))
+        .await
+}
+
+async fn set_comment_status(
+    state: State<'_, Mutex<AppState>>,
+    ids: Vec<i32>,
+    status: &str,
+) -> Result<u64, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.comments
+        SET status = $1, moderated_at = NOW()
+        WHERE id = ANY($2) AND status <> $1
+        "#,
+        status,
+        &ids
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected())
+}
+
+// The moderation commands take several ids so a whole queue can be handled at once,
+// and return how many comments changed
+#[tauri::command]
+pub async fn approve_comments(
+    state: State<'_, Mutex<AppState>>,
+    ids: Vec<i32>,
+) -> Result<u64, String> {
+    set_comment_status(state, ids, "approved").await
+}
+
+#[tauri::command]
+pub async fn reject_comments(
+    state: State<'_, Mutex<AppState>>,
+    ids: Vec<i32>,
+) -> Result<u64, String> {
+    set_comment_status(state, ids, "rejected").await
+}
+
+#[tauri::command]
+pub async fn mark_comments_as_spam(
+    state: State<'_, Mutex<AppState>>,
+    ids: Vec<i32>,
+) -> Result<u64, String> {
+    set_comment_status(state, ids, "spam").await
+}
+
+// Replies go straight out approved, and replying to a pending comment approves it
+#[tauri::command]
+pub async fn reply_to_comment(
+    state: State<'_, Mutex<AppState>>,
+    comment_id: i32,
+    reply: CommentReply,
+) -> Result<Comment, String> {
+    reply.validate()?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let parent = fetch_comment(&mut tx, comment_id).await?;
+    if matches!(parent.status.as_str(), "rejected" | "spam") {
+        return Err(format!(
+            "Cannot reply to a comment marked as {}",
+            parent.status
+        ));
+    }
+
+    if parent.status == "pending" {
+        sqlx::query!(
+            r#"
+            UPDATE tadgh_blog.comments
+            SET status = 'approved', moderated_at = NOW()
+            WHERE id = $1
+            "#,
+            parent.id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO tadgh_blog.comments
+        (blog_post_id, parent_id, author_name, body, status, is_admin_reply, scored_at, moderated_at)
+        VALUES ($1, $2, $3, $4, 'approved', TRUE, NOW(), NOW())
+        RETURNING id
+        "#,
+        parent.blog_post_id,
+        parent.id,
+        reply.author_name.trim(),
+        reply.body.trim()
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let created = fetch_comment(&mut tx, id).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+// Replies to deleted comments are deleted with them
+#[tauri::command]
+pub async fn delete_comments(
+    state: State<'_, Mutex<AppState>>,
+    ids: Vec<i32>,
+) -> Result<u64, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let result = sqlx::query!("DELETE FROM tadgh_blog.comments WHERE id = ANY($1)", &ids)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(result.rows_affected())
+}
+
+// Counts for one post, or for every post that has comments
+#[tauri::command]
+pub async fn get_comment_counts(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: Option<i32>,
+) -> Result<Vec<PostCommentCounts>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        PostCommentCounts,
+        r#"
+        SELECT
+            bp.id as blog_post_id,
+            bp.title,
+            COUNT(c.id) as "total!",
+            COUNT(c.id) FILTER (WHERE c.status = 'pending') as "pending!",
+            COUNT(c.id) FILTER (WHERE c.status = 'approved') as "approved!",
+            COUNT(c.id) FILTER (WHERE c.status = 'rejected') as "rejected!",
+            COUNT(c.id) FILTER (WHERE c.status = 'spam') as "spam!"
+        FROM tadgh_blog.blog_posts bp
+        LEFT JOIN tadgh_blog.comments c ON c.blog_post_id = bp.id
+        WHERE $1::int IS NULL OR bp.id = $1
+        GROUP BY bp.id, bp.title
+        HAVING $1::int IS NOT NULL OR COUNT(c.id) > 0
+        ORDER BY COUNT(c.id) FILTER (WHERE c.status = 'pending') DESC, bp.id ASC
+        "#,
+        blog_post_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+fn count_links(text: &str) -> usize {
+    let lower = text.to_lowercase();
+    ["http://", "https://", "www."]
+        .iter()
+        .map(|marker| lower.matches(marker).count())
+        .sum::<usize>()
+        - lower.matches("://www.").count() // "https://www." is one link
+}
+
+// Heuristic score from 0 to 100 with the reasons behind it
+async fn score_comment(
+    conn: &mut PgConnection,
+    comment: &Comment,
+    blocklist: &[String],
+) -> Result<(i32, Vec<String>), String> {
+    let mut score = 0;
+    let mut reasons = Vec::new();
+
+    let links = count_links(&comment.body);
+    if links > 0 {
+        score += 15 * links.min(3) as i32;
+        reasons.push(format!(
+            "{} link{} in the comment",
+            links,
+            if links == 1 { "" } else { "s" }
+        ));
+    }
+    if comment
+        .author_url
+        .as_deref()
+        .is_some_and(|url| !url.trim().is_empty())
+        && links > 0
+    {
+        score += 5;
+        reasons.push("Links in both the comment and the author URL".to_string());
+    }
+
+    let text = format!(
+        "{} {} {}",
+        comment.author_name,
+        comment.author_email.as_deref().unwrap_or_default(),
+        comment.body
+    )
+    .to_lowercase();
+    let blocked: Vec<&String> = blocklist
+        .iter()
+        .filter(|term| text.contains(term.as_str()))
+        .collect();
+    if !blocked.is_empty() {
+        score += 30 * blocked.len().min(2) as i32;
+        for term in blocked {
+            reasons.push(format!("Blocked term \"{}\"", term));
+        }
+    }
+
+    if let Some(ip_address) = comment.ip_address.as_deref() {
+        let since = comment.created_at - Duration::hours(1);
+        let activity = sqlx::query!(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM tadgh_blog.blog_post_ips
+                 WHERE ip_address = $1 AND viewed_at BETWEEN $2 AND $3) as "views!",
+                (SELECT COUNT(*) FROM tadgh_blog.comments
+                 WHERE ip_address = $1 AND created_at BETWEEN $2 AND $3 AND id <> $4) as "comments!"
+            "#,
+            ip_address,
+            since,
+            comment.created_at,
+            comment.id
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if activity.views > ABUSIVE_VIEWS_PER_HOUR {
+            score += 40;
+            reasons.push(format!(
+                "IP viewed {} pages in the hour before commenting",
+                activity.views
+            ));
+        }
+        if activity.comments >= ABUSIVE_COMMENTS_PER_HOUR {
+            score += 20;
+            reasons.push(format!(
+                "IP left {} other comments in the hour before",
+                activity.comments
+            ));
+        }
+    }
+
+    Ok((score.min(100), reasons))
+}
+
+// Scores the given comments, or every pending comment that hasn't been scored yet.
+// With `mark_spam`, pending comments at or above the threshold are moved to spam.
+#[tauri::command]
+pub async fn score_comments(
+    state: State<'_, Mutex<AppState>>,
+    ids: Option<Vec<i32>>,
+    mark_spam: Option<bool>,
+) -> Result<SpamScoreReport, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let blocklist: Vec<String> =
+        sqlx::query_scalar!("SELECT LOWER(term) as \"term!\" FROM tadgh_blog.comment_blocklist")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let comment_ids = sqlx::query_scalar!(
+        r#"
+        SELECT id FROM tadgh_blog.comments
+        WHERE CASE WHEN $1::int[] IS NULL
+                   THEN status = 'pending' AND scored_at IS NULL
+                   ELSE id = ANY($1) END
+          AND NOT is_admin_reply
+        ORDER BY id
+        "#,
+        ids.as_deref()
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mark_spam = mark_spam.unwrap_or(false);
+    let mut report = SpamScoreReport {
+        scored: 0,
+        likely_spam: 0,
+        marked_spam: 0,
+    };
+    for id in comment_ids {
+        let comment = fetch_comment(&mut tx, id).await?;
+        let (score, reasons) = score_comment(&mut tx, &comment, &blocklist).await?;
+        let to_spam = mark_spam && score >= SPAM_THRESHOLD && comment.status == "pending";
+
+        sqlx::query!(
+            r#"
+            UPDATE tadgh_blog.comments
+            SET spam_score = $1,
+                spam_reasons = $2,
+                scored_at = NOW(),
+                status = CASE WHEN $3 THEN 'spam' ELSE status END,
+                moderated_at = CASE WHEN $3 THEN NOW() ELSE moderated_at END
+            WHERE id = $4
+            "#,
+            score,
+            &reasons,
+            to_spam,
+            id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        report.scored += 1;
+        if score >= SPAM_THRESHOLD {
+            report.likely_spam += 1;
+        }
+        if to_spam {
+            report.marked_spam += 1;
+        }
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn get_comment_blocklist(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<BlocklistTerm>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        BlocklistTerm,
+        "SELECT id, term, created_at FROM tadgh_blog.comment_blocklist ORDER BY term ASC"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_comment_blocklist_term(
+    state: State<'_, Mutex<AppState>>,
+    term: String,
+) -> Result<BlocklistTerm, String> {
+    let term = term.trim().to_lowercase();
+    Validator::default()
+        .required("term", &term)
+        .max_length("term", Some(&term), 100)
+        .finish()?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        BlocklistTerm,
+        r#"
+        INSERT INTO tadgh_blog.comment_blocklist (term)
+        VALUES ($1)
+        ON CONFLICT (term) DO UPDATE SET term = EXCLUDED.term
+        RETURNING id, term, created_at
+        "#,
+        term
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_comment_blocklist_term(
+    state: State<'_, Mutex<AppState>>,
+    id: i32,
+) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let result = sqlx::query!("DELETE FROM tadgh_blog.comment_blocklist WHERE id = $1", id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err("Blocklist term not found".to_string());
+    }
+    Ok(())
+}
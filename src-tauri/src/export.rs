@@ -0,0 +1,390 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::authors::{get_all_blog_post_authors, get_all_project_authors, Author, AuthorLink};
+use crate::images::ImageMetadata;
+use crate::seo::Seo;
+use crate::slugs::permalink;
+use crate::tags::Tag;
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedPost {
+    pub id: i32,
+    pub slug: String,
+    pub permalink: String,
+    pub title: String,
+    pub description: String,
+    pub created: NaiveDate,
+    pub file_name: String,
+    pub published: bool,
+    pub tags: Vec<String>,    // Tag slugs
+    pub authors: Vec<String>, // Author slugs in credit order
+    pub image: Option<ImageMetadata>,
+    pub seo: Seo,
+    pub series: Option<ExportedSeriesPosition>,
+}
+
+// Position among the exported posts of the series, so drafts never leave gaps
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedSeriesPosition {
+    pub slug: String,
+    pub position: usize,
+    pub total: usize,
+    pub previous: Option<String>, // Post slugs
+    pub next: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedProject {
+    pub id: i32,
+    pub slug: String,
+    pub permalink: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub created: Option<NaiveDate>,
+    pub released: bool,
+    pub live: bool,
+    pub tags: Vec<String>,
+    pub authors: Vec<String>,
+    pub image: Option<ImageMetadata>,
+    pub seo: Seo,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedCategory {
+    pub id: i32,
+    pub slug: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedAuthor {
+    pub id: i32,
+    pub slug: String,
+    pub permalink: String,
+    pub name: String,
+    pub bio: Option<String>,
+    pub avatar_name: Option<String>,
+    pub links: Vec<AuthorLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedSeries {
+    pub id: i32,
+    pub slug: String,
+    pub permalink: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub posts: Vec<String>, // Post slugs in reading order
+}
+
+// Everything the static site build needs besides the markdown and image files
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SiteExport {
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub posts: Vec<ExportedPost>,
+    pub projects: Vec<ExportedProject>,
+    pub tags: Vec<Tag>,
+    pub categories: Vec<ExportedCategory>,
+    pub series: Vec<ExportedSeries>,
+    pub authors: Vec<ExportedAuthor>,
+    pub images: Vec<ImageMetadata>, // Details for inline images as well as heroes
+}
+
+// Hero image details, with empty details when the image has no row in the images table
+fn hero_image(
+    image_name: Option<String>,
+    alt_text: Option<String>,
+    caption: Option<String>,
+    credit: Option<String>,
+    license: Option<String>,
+    focal_x: Option<f32>,
+    focal_y: Option<f32>,
+) -> Option<ImageMetadata> {
+    image_name
+        .filter(|name| !name.is_empty())
+        .map(|file_name| ImageMetadata {
+            file_name,
+            alt_text,
+            caption,
+            credit,
+            license,
+            focal_x,
+            focal_y,
+        })
+}
+
+// Exports published posts and released projects (or everything with `include_drafts`)
+// as JSON, optionally writing it to `output_path`
+#[tauri::command]
+pub async fn export_site_data(
+    state: State<'_, Mutex<AppState>>,
+    output_path: Option<String>,
+    include_drafts: Option<bool>,
+) -> Result<SiteExport, String> {
+    let include_drafts = include_drafts.unwrap_or(false);
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    let mut posts: Vec<ExportedPost> = sqlx::query!(
+        r#"
+        SELECT
+            bp.id, bp.slug, bp.title, bp.description, bp.created, bp.file_name, bp.published,
+            bp.meta_title, bp.meta_description, bp.canonical_url, bp.og_image, bp.noindex,
+            bp.image_name, i.alt_text as "alt_text?", i.caption as "caption?", i.credit as "credit?",
+            i.license as "license?", i.focal_x as "focal_x?", i.focal_y as "focal_y?",
+            ARRAY(
+                SELECT t.slug FROM tadgh_blog.blog_post_tags bpt
+                JOIN tadgh_blog.tags t ON t.id = bpt.tag_id
+                WHERE bpt.blog_post_id = bp.id
+                ORDER BY t.name
+            ) as "tags!"
+        FROM tadgh_blog.blog_posts bp
+        LEFT JOIN tadgh_blog.images i ON i.file_name = bp.image_name
+        WHERE bp.published OR $1
+        ORDER BY bp.created DESC, bp.id DESC
+        "#,
+        include_drafts
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|row| ExportedPost {
+        permalink: permalink("blog_post", &row.slug),
+        image: hero_image(
+            row.image_name,
+            row.alt_text,
+            row.caption,
+            row.credit,
+            row.license,
+            row.focal_x,
+            row.focal_y,
+        ),
+        seo: Seo {
+            meta_title: row.meta_title,
+            meta_description: row.meta_description,
+            canonical_url: row.canonical_url,
+            og_image: row.og_image,
+            noindex: row.noindex,
+        },
+        id: row.id,
+        slug: row.slug,
+        title: row.title,
+        description: row.description,
+        created: row.created,
+        file_name: row.file_name,
+        published: row.published,
+        tags: row.tags,
+        authors: Vec::new(),
+        series: None,
+    })
+    .collect();
+
+    let mut projects: Vec<ExportedProject> = sqlx::query!(
+        r#"
+        SELECT
+            p.id, p.slug, p.title, p.description, p.url, p.created, p.released, p.live,
+            p.meta_title, p.meta_description, p.canonical_url, p.og_image, p.noindex,
+            p.image_name, i.alt_text as "alt_text?", i.caption as "caption?", i.credit as "credit?",
+            i.license as "license?", i.focal_x as "focal_x?", i.focal_y as "focal_y?",
+            ARRAY(
+                SELECT t.slug FROM tadgh_blog.project_tags pt
+                JOIN tadgh_blog.tags t ON t.id = pt.tag_id
+                WHERE pt.project_id = p.id
+                ORDER BY t.name
+            ) as "tags!"
+        FROM tadgh_blog.projects p
+        LEFT JOIN tadgh_blog.images i ON i.file_name = p.image_name
+        WHERE p.released OR $1
+        ORDER BY p.created DESC NULLS LAST, p.id DESC
+        "#,
+        include_drafts
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .map(|row| ExportedProject {
+        permalink: permalink("project", &row.slug),
+        image: hero_image(
+            row.image_name,
+            row.alt_text,
+            row.caption,
+            row.credit,
+            row.license,
+            row.focal_x,
+            row.focal_y,
+        ),
+        seo: Seo {
+            meta_title: row.meta_title,
+            meta_description: row.meta_description,
+            canonical_url: row.canonical_url,
+            og_image: row.og_image,
+            noindex: row.noindex,
+        },
+        id: row.id,
+        slug: row.slug,
+        title: row.title,
+        description: row.description,
+        url: row.url,
+        created: row.created,
+        released: row.released,
+        live: row.live,
+        tags: row.tags,
+        authors: Vec::new(),
+    })
+    .collect();
+
+    let tags = sqlx::query_as!(
+        Tag,
+        "SELECT id, name, slug FROM tadgh_blog.tags ORDER BY name ASC"
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let categories = sqlx::query_as!(
+        ExportedCategory,
+        r#"
+        SELECT
+            c.id, c.slug, c.name, c.description,
+            ARRAY(
+                SELECT t.slug FROM tadgh_blog.tag_categories tc
+                JOIN tadgh_blog.tags t ON t.id = tc.tag_id
+                WHERE tc.category_id = c.id
+                ORDER BY t.name
+            ) as "tags!"
+        FROM tadgh_blog.categories c
+        ORDER BY c.name ASC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Only posts that made it into the export are listed in their series
+    let exported_ids: Vec<i32> = posts.iter().map(|post| post.id).collect();
+    let series_rows = sqlx::query!(
+        r#"
+        SELECT s.id, s.slug, s.name, s.description,
+            ARRAY(
+                SELECT bp.slug FROM tadgh_blog.series_posts sp
+                JOIN tadgh_blog.blog_posts bp ON bp.id = sp.blog_post_id
+                WHERE sp.series_id = s.id AND sp.blog_post_id = ANY($1)
+                ORDER BY sp.position
+            ) as "posts!"
+        FROM tadgh_blog.series s
+        ORDER BY s.name ASC
+        "#,
+        &exported_ids
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let series: Vec<ExportedSeries> = series_rows
+        .into_iter()
+        .filter(|row| !row.posts.is_empty())
+        .map(|row| ExportedSeries {
+            permalink: permalink("series", &row.slug),
+            id: row.id,
+            slug: row.slug,
+            name: row.name,
+            description: row.description,
+            posts: row.posts,
+        })
+        .collect();
+
+    let mut positions = HashMap::new();
+    for entry in &series {
+        for (index, slug) in entry.posts.iter().enumerate() {
+            positions.insert(
+                slug.clone(),
+                ExportedSeriesPosition {
+                    slug: entry.slug.clone(),
+                    position: index + 1,
+                    total: entry.posts.len(),
+                    previous: index.checked_sub(1).map(|i| entry.posts[i].clone()),
+                    next: entry.posts.get(index + 1).cloned(),
+                },
+            );
+        }
+    }
+    for post in &mut posts {
+        post.series = positions.remove(&post.slug);
+    }
+
+    // Only authors credited on exported posts or projects are listed
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    let mut post_authors = get_all_blog_post_authors(&mut conn).await?;
+    let mut project_authors = get_all_project_authors(&mut conn).await?;
+    let mut authors: Vec<ExportedAuthor> = Vec::new();
+    let mut credit = |credited: Vec<Author>| -> Vec<String> {
+        credited
+            .into_iter()
+            .map(|author| {
+                let slug = author.slug.clone();
+                if !authors.iter().any(|exported| exported.id == author.id) {
+                    authors.push(ExportedAuthor {
+                        permalink: permalink("author", &author.slug),
+                        id: author.id,
+                        slug: author.slug,
+                        name: author.name,
+                        bio: author.bio,
+                        avatar_name: author.avatar_name,
+                        links: author.links,
+                    });
+                }
+                slug
+            })
+            .collect()
+    };
+    for post in &mut posts {
+        post.authors = credit(post_authors.remove(&post.id).unwrap_or_default());
+    }
+    for project in &mut projects {
+        project.authors = credit(project_authors.remove(&project.id).unwrap_or_default());
+    }
+    authors.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let images = sqlx::query_as!(
+        ImageMetadata,
+        r#"
+        SELECT file_name, alt_text, caption, credit, license, focal_x, focal_y
+        FROM tadgh_blog.images
+        ORDER BY file_name ASC
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let export = SiteExport {
+        generated_at: chrono::Utc::now(),
+        posts,
+        projects,
+        tags,
+        categories,
+        series,
+        authors,
+        images,
+    };
+
+    if let Some(path) = output_path {
+        let json = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+        tokio::fs::write(&path, json)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(export)
+}
@@ -0,0 +1,472 @@
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::authors::{get_all_blog_post_authors, Author};
+use crate::preview::render_markdown_for_site;
+use crate::settings::load_current_profile;
+use crate::slugs::{image_permalink, permalink};
+use crate::AppState;
+
+const DEFAULT_FEED_LIMIT: usize = 20;
+
+// RSS, Atom and JSON Feed, written to every feed folder
+const FEED_FILES: [&str; 3] = ["feed.xml", "atom.xml", "feed.json"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FeedOptions {
+    pub full_content: Option<bool>, // Include the rendered markdown, off by default
+    pub per_tag: Option<bool>,      // On by default
+    pub per_category: Option<bool>, // On by default
+    pub limit: Option<usize>,       // Newest posts per feed
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GeneratedFeed {
+    pub format: String, // "rss", "atom" or "json"
+    pub scope: String,  // "site", "tag" or "category"
+    pub slug: Option<String>,
+    pub path: String, // Relative to the output folder
+    pub items: usize,
+}
+
+struct FeedPost {
+    slug: String,
+    title: String,
+    description: String,
+    created: NaiveDate,
+    tag_ids: Vec<i32>,
+    tags: Vec<String>,
+    authors: Vec<Author>,
+    content: Option<String>,
+}
+
+// One set of feed documents, e.g. every post or the posts of one tag
+struct Feed<'a> {
+    scope: &'static str,
+    slug: Option<String>,
+    title: String,
+    base_path: String, // Site path the feeds are published under, "" for the site feeds
+    posts: Vec<&'a FeedPost>,
+}
+
+// Also used for the sitemap
+pub fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn cdata(value: &str) -> String {
+    format!("<![CDATA[{}]]>", value.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn author_url(author: &Author, site_url: &str) -> String {
+    format!("{}{}", site_url, permalink("author", &author.slug))
+}
+
+fn published_at(created: NaiveDate) -> chrono::DateTime<chrono::Utc> {
+    created.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn render_rss(feed: &Feed, site_url: &str) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\" \
+         xmlns:content=\"http://purl.org/rss/1.0/modules/content/\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n<channel>\n",
+    );
+    xml.push_str(&format!("<title>{}</title>\n", escape_xml(&feed.title)));
+    let home = format!("{}{}/", site_url, feed.base_path);
+    xml.push_str(&format!("<link>{}</link>\n", escape_xml(&home)));
+    xml.push_str(&format!(
+        "<description>{}</description>\n",
+        escape_xml(&feed.title)
+    ));
+    xml.push_str(&format!(
+        "<atom:link href=\"{}feed.xml\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape_xml(&home)
+    ));
+    if let Some(latest) = feed.posts.first() {
+        xml.push_str(&format!(
+            "<lastBuildDate>{}</lastBuildDate>\n",
+            published_at(latest.created).to_rfc2822()
+        ));
+    }
+
+    for post in &feed.posts {
+        let url = format!("{}{}", site_url, permalink("blog_post", &post.slug));
+        xml.push_str("<item>\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape_xml(&post.title)));
+        xml.push_str(&format!("<link>{}</link>\n", escape_xml(&url)));
+        xml.push_str(&format!(
+            "<guid isPermaLink=\"true\">{}</guid>\n",
+            escape_xml(&url)
+        ));
+        xml.push_str(&format!(
+            "<pubDate>{}</pubDate>\n",
+            published_at(post.created).to_rfc2822()
+        ));
+        xml.push_str(&format!(
+            "<description>{}</description>\n",
+            escape_xml(&post.description)
+        ));
+        // RSS <author> has to be an email address, so names go in dc:creator
+        for author in &post.authors {
+            xml.push_str(&format!(
+                "<dc:creator>{}</dc:creator>\n",
+                escape_xml(&author.name)
+            ));
+        }
+        for tag in &post.tags {
+            xml.push_str(&format!("<category>{}</category>\n", escape_xml(tag)));
+        }
+        if let Some(content) = &post.content {
+            xml.push_str(&format!(
+                "<content:encoded>{}</content:encoded>\n",
+                cdata(content)
+            ));
+        }
+        xml.push_str("</item>\n");
+    }
+
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+fn render_atom(feed: &Feed, site_url: &str) -> String {
+    let home = format!("{}{}/", site_url, feed.base_path);
+    let updated = feed
+        .posts
+        .first()
+        .map(|post| published_at(post.created))
+        .unwrap_or_else(chrono::Utc::now);
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <feed xmlns=\"http://www.w3.org/2005/Atom\">\n",
+    );
+    xml.push_str(&format!("<title>{}</title>\n", escape_xml(&feed.title)));
+    xml.push_str(&format!("<id>{}</id>\n", escape_xml(&home)));
+    xml.push_str(&format!("<link href=\"{}\"/>\n", escape_xml(&home)));
+    xml.push_str(&format!(
+        "<link rel=\"self\" href=\"{}atom.xml\"/>\n",
+        escape_xml(&home)
+    ));
+    xml.push_str(&format!("<updated>{}</updated>\n", updated.to_rfc3339()));
+
+    for post in &feed.posts {
+        let url = format!("{}{}", site_url, permalink("blog_post", &post.slug));
+        let date = published_at(post.created).to_rfc3339();
+        xml.push_str("<entry>\n");
+        xml.push_str(&format!("<title>{}</title>\n", escape_xml(&post.title)));
+        xml.push_str(&format!("<id>{}</id>\n", escape_xml(&url)));
+        xml.push_str(&format!("<link href=\"{}\"/>\n", escape_xml(&url)));
+        xml.push_str(&format!("<published>{}</published>\n", date));
+        xml.push_str(&format!("<updated>{}</updated>\n", date));
+        xml.push_str(&format!(
+            "<summary>{}</summary>\n",
+            escape_xml(&post.description)
+        ));
+        for author in &post.authors {
+            xml.push_str(&format!(
+                "<author><name>{}</name><uri>{}</uri></author>\n",
+                escape_xml(&author.name),
+                escape_xml(&author_url(author, site_url))
+            ));
+        }
+        for tag in &post.tags {
+            xml.push_str(&format!("<category term=\"{}\"/>\n", escape_xml(tag)));
+        }
+        if let Some(content) = &post.content {
+            xml.push_str(&format!(
+                "<content type=\"html\">{}</content>\n",
+                escape_xml(content)
+            ));
+        }
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn render_json_feed(feed: &Feed, site_url: &str) -> Result<String, String> {
+    let items: Vec<serde_json::Value> = feed
+        .posts
+        .iter()
+        .map(|post| {
+            let url = format!("{}{}", site_url, permalink("blog_post", &post.slug));
+            let mut item = serde_json::json!({
+                "id": url,
+                "url": url,
+                "title": post.title,
+                "summary": post.description,
+                "date_published": published_at(post.created).to_rfc3339(),
+                "tags": post.tags,
+            });
+            if !post.authors.is_empty() {
+                let authors: Vec<serde_json::Value> = post
+                    .authors
+                    .iter()
+                    .map(|author| {
+                        let mut value = serde_json::json!({
+                            "name": author.name,
+                            "url": author_url(author, site_url),
+                        });
+                        if let Some(avatar) = author.avatar_name.as_deref() {
+                            value["avatar"] = serde_json::Value::from(format!(
+                                "{}{}",
+                                site_url,
+                                image_permalink(avatar)
+                            ));
+                        }
+                        value
+                    })
+                    .collect();
+                item["authors"] = serde_json::Value::from(authors);
+            }
+            if let Some(content) = &post.content {
+                item["content_html"] = serde_json::Value::from(content.as_str());
+            } else {
+                item["content_text"] = serde_json::Value::from(post.description.as_str());
+            }
+            item
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&serde_json::json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": feed.title,
+        "home_page_url": format!("{}{}/", site_url, feed.base_path),
+        "feed_url": format!("{}{}/feed.json", site_url, feed.base_path),
+        "items": items,
+    }))
+    .map_err(|e| e.to_string())
+}
+
+async fn write_feed(
+    output_folder: &Path,
+    feed: &Feed<'_>,
+    site_url: &str,
+) -> Result<Vec<GeneratedFeed>, String> {
+    let folder = output_folder.join(feed.base_path.trim_start_matches('/'));
+    tokio::fs::create_dir_all(&folder)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let [rss, atom, json] = FEED_FILES;
+    let documents = [
+        ("rss", rss, render_rss(feed, site_url)),
+        ("atom", atom, render_atom(feed, site_url)),
+        ("json", json, render_json_feed(feed, site_url)?),
+    ];
+
+    let mut generated = Vec::new();
+    for (format, file_name, document) in documents {
+        tokio::fs::write(folder.join(file_name), document)
+            .await
+            .map_err(|e| e.to_string())?;
+        generated.push(GeneratedFeed {
+            format: format.to_string(),
+            scope: feed.scope.to_string(),
+            slug: feed.slug.clone(),
+            path: format!("{}/{}", feed.base_path, file_name)
+                .trim_start_matches('/')
+                .to_string(),
+            items: feed.posts.len(),
+        });
+    }
+    Ok(generated)
+}
+
+// Feeds of tags and categories that were deleted, renamed or left without posts would
+// otherwise still be published. Only the feed files are removed, anything else the
+// site keeps in those folders stays.
+async fn remove_stale_feeds(
+    output_folder: &Path,
+    generated: &[GeneratedFeed],
+) -> Result<(), String> {
+    for scope_folder in ["tags", "categories"] {
+        let Ok(mut entries) = tokio::fs::read_dir(output_folder.join(scope_folder)).await else {
+            continue;
+        };
+        while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let slug = entry.file_name().to_string_lossy().to_string();
+            for file_name in FEED_FILES {
+                let path = format!("{}/{}/{}", scope_folder, slug, file_name);
+                if generated.iter().any(|feed| feed.path == path) {
+                    continue;
+                }
+                match tokio::fs::remove_file(entry.path().join(file_name)).await {
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.to_string()),
+                    _ => {}
+                }
+            }
+            // Only succeeds when nothing else is left in the folder
+            let _ = tokio::fs::remove_dir(entry.path()).await;
+        }
+    }
+    Ok(())
+}
+
+// Writes RSS 2.0, Atom and JSON Feed documents for published posts to the site output
+// folder: feed.xml, atom.xml and feed.json at the top, and under tags/<slug>/ and
+// categories/<slug>/ for the per-tag and per-category feeds
+#[tauri::command]
+pub async fn generate_feeds(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    options: Option<FeedOptions>,
+) -> Result<Vec<GeneratedFeed>, String> {
+    let options = options.unwrap_or_default();
+    let limit = options.limit.unwrap_or(DEFAULT_FEED_LIMIT).max(1);
+
+    let profile = load_current_profile(&app).await?;
+    let site_url = profile.site_url().ok_or("Site URL not configured")?;
+    let site_title = profile.site_title().unwrap_or("Blog");
+    let output_folder = profile
+        .site_output_folder()
+        .ok_or("Site output path not configured")?;
+
+    let (rows, tags, categories, mut authors) = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bp.id, bp.slug, bp.title, bp.description, bp.created, bp.file_name,
+                ARRAY(
+                    SELECT t.id FROM tadgh_blog.blog_post_tags bpt
+                    JOIN tadgh_blog.tags t ON t.id = bpt.tag_id
+                    WHERE bpt.blog_post_id = bp.id
+                    ORDER BY t.name
+                ) as "tag_ids!",
+                ARRAY(
+                    SELECT t.name FROM tadgh_blog.blog_post_tags bpt
+                    JOIN tadgh_blog.tags t ON t.id = bpt.tag_id
+                    WHERE bpt.blog_post_id = bp.id
+                    ORDER BY t.name
+                ) as "tags!"
+            FROM tadgh_blog.blog_posts bp
+            WHERE bp.published
+            ORDER BY bp.created DESC, bp.id DESC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let tags = sqlx::query!("SELECT id, name, slug FROM tadgh_blog.tags ORDER BY name ASC")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let categories = sqlx::query!(
+            r#"
+            SELECT
+                c.name, c.slug,
+                ARRAY(
+                    SELECT tc.tag_id FROM tadgh_blog.tag_categories tc
+                    WHERE tc.category_id = c.id
+                ) as "tag_ids!"
+            FROM tadgh_blog.categories c
+            ORDER BY c.name ASC
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+        let authors = get_all_blog_post_authors(&mut conn).await?;
+
+        (rows, tags, categories, authors)
+    };
+
+    let blog_folder = profile.blog_folder();
+    let full_content = options.full_content.unwrap_or(false);
+    let mut posts = Vec::with_capacity(rows.len());
+    for row in rows {
+        let content = match (&blog_folder, full_content) {
+            (Some(folder), true) => tokio::fs::read_to_string(folder.join(&row.file_name))
+                .await
+                .ok()
+                .map(|markdown| render_markdown_for_site(&markdown, site_url)),
+            _ => None,
+        };
+        posts.push(FeedPost {
+            slug: row.slug,
+            title: row.title,
+            description: row.description,
+            created: row.created,
+            tag_ids: row.tag_ids,
+            tags: row.tags,
+            authors: authors.remove(&row.id).unwrap_or_default(),
+            content,
+        });
+    }
+
+    let mut feeds = vec![Feed {
+        scope: "site",
+        slug: None,
+        title: site_title.to_string(),
+        base_path: String::new(),
+        posts: posts.iter().take(limit).collect(),
+    }];
+
+    if options.per_tag.unwrap_or(true) {
+        for tag in &tags {
+            let tagged: Vec<&FeedPost> = posts
+                .iter()
+                .filter(|post| post.tag_ids.contains(&tag.id))
+                .take(limit)
+                .collect();
+            if !tagged.is_empty() {
+                feeds.push(Feed {
+                    scope: "tag",
+                    slug: Some(tag.slug.clone()),
+                    title: format!("{} - {}", site_title, tag.name),
+                    base_path: permalink("tag", &tag.slug),
+                    posts: tagged,
+                });
+            }
+        }
+    }
+
+    if options.per_category.unwrap_or(true) {
+        for category in &categories {
+            let categorized: Vec<&FeedPost> = posts
+                .iter()
+                .filter(|post| post.tag_ids.iter().any(|id| category.tag_ids.contains(id)))
+                .take(limit)
+                .collect();
+            if !categorized.is_empty() {
+                feeds.push(Feed {
+                    scope: "category",
+                    slug: Some(category.slug.clone()),
+                    title: format!("{} - {}", site_title, category.name),
+                    base_path: permalink("category", &category.slug),
+                    posts: categorized,
+                });
+            }
+        }
+    }
+
+    let mut generated = Vec::new();
+    for feed in &feeds {
+        generated.extend(write_feed(&output_folder, feed, site_url).await?);
+    }
+    remove_stale_feeds(&output_folder, &generated).await?;
+
+    Ok(generated)
+}
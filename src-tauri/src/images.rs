@@ -0,0 +1,1029 @@
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use img_parts::jpeg::{markers, Jpeg};
+use img_parts::png::Png;
+use img_parts::webp::{WebP, CHUNK_XMP};
+use img_parts::ImageEXIF;
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::OpenOptions;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::blog::copy_name;
+use crate::integrity::list_files;
+use crate::preview::{image_file_name, markdown_references};
+use crate::revisions::record_blog_post_revision;
+use crate::search::index_blog_body;
+use crate::settings::load_current_profile;
+use crate::slugs::slugify;
+use crate::validation::{Validate, Validator};
+use crate::watcher::FolderWatcher;
+use crate::AppState;
+
+const DEFAULT_WIDTHS: [u32; 3] = [480, 960, 1440];
+const DEFAULT_THUMBNAIL_SIZE: u32 = 320;
+const DEFAULT_QUALITY: u8 = 80;
+const AVIF_SPEED: u8 = 8; // 1 (slowest, smallest) to 10
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IngestImageOptions {
+    pub widths: Option<Vec<u32>>, // Responsive widths, only those narrower than the source are made
+    pub thumbnail_size: Option<u32>, // Edge of the square thumbnail, 0 skips it
+    pub formats: Option<Vec<String>>, // Extra formats, "webp" and/or "avif"; both by default
+    pub quality: Option<u8>,      // 1-100, used by the JPEG and AVIF encoders
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageVariant {
+    pub path: String, // Relative to the images folder
+    pub width: u32,
+    pub height: u32,
+    pub format: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngestedImage {
+    pub image_name: String,
+    pub width: u32,
+    pub height: u32,
+    pub variants: Vec<ImageVariant>,
+}
+
+// Generated sizes and formats of an image are kept together, out of the top level of
+// the images folder, so renaming or deleting the image can take them along
+pub fn variants_folder(images_folder: &Path, image_name: &str) -> PathBuf {
+    images_folder.join("variants").join(image_name)
+}
+
+// Slugified file name that is not taken by another image or its variants
+fn available_name(images_folder: &Path, source: &Path, format: ImageFormat) -> String {
+    let stem = source
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(slugify)
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| "image".to_string());
+    let extension = format.extensions_str()[0];
+
+    let mut candidate = format!("{}.{}", stem, extension);
+    let mut suffix = 2;
+    while images_folder.join(&candidate).exists()
+        || variants_folder(images_folder, &candidate).exists()
+    {
+        candidate = format!("{}-{}.{}", stem, suffix, extension);
+        suffix += 1;
+    }
+    candidate
+}
+
+// Copies an image and its variants to a free "<name>-copy" file name, returning the new name
+pub async fn copy_image(images_folder: &Path, image_name: &str) -> Result<String, String> {
+    let source = images_folder.join(image_name);
+    if !source.exists() {
+        return Err(format!("Image not found: {}", image_name));
+    }
+
+    let mut attempt = 1;
+    let mut new_name = copy_name(image_name, attempt);
+    while images_folder.join(&new_name).exists()
+        || variants_folder(images_folder, &new_name).exists()
+    {
+        attempt += 1;
+        new_name = copy_name(image_name, attempt);
+    }
+
+    tokio::fs::copy(&source, images_folder.join(&new_name))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let variants = variants_folder(images_folder, image_name);
+    if variants.is_dir() {
+        let target = variants_folder(images_folder, &new_name);
+        tokio::fs::create_dir_all(&target)
+            .await
+            .map_err(|e| e.to_string())?;
+        for file_name in list_files(&variants).await? {
+            tokio::fs::copy(variants.join(&file_name), target.join(&file_name))
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(new_name)
+}
+
+// Removes an image copied by `copy_image`, used to undo a copy when the database work fails
+pub async fn remove_image_copy(images_folder: &Path, image_name: &str) {
+    let _ = tokio::fs::remove_file(images_folder.join(image_name)).await;
+    let _ = tokio::fs::remove_dir_all(variants_folder(images_folder, image_name)).await;
+}
+
+// Gives a copied image the same alt text, caption and credits as the original
+pub async fn copy_image_metadata(
+    conn: &mut PgConnection,
+    image_name: Option<&str>,
+    new_name: &str,
+) -> Result<(), String> {
+    sqlx::query!(
+        r#"
+        INSERT INTO tadgh_blog.images (file_name, alt_text, caption, credit, license, focal_x, focal_y)
+        SELECT $2, alt_text, caption, credit, license, focal_x, focal_y
+        FROM tadgh_blog.images
+        WHERE file_name = $1
+        ON CONFLICT (file_name) DO NOTHING
+        "#,
+        image_name,
+        new_name
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Drops EXIF (including GPS), XMP, IPTC and comments without re-encoding the image
+fn strip_metadata(format: ImageFormat, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    match format {
+        ImageFormat::Jpeg => {
+            let mut jpeg = Jpeg::from_bytes(bytes.into()).map_err(|e| e.to_string())?;
+            for marker in [markers::APP1, markers::APP13, markers::COM] {
+                jpeg.remove_segments_by_marker(marker);
+            }
+            Ok(jpeg.encoder().bytes().to_vec())
+        }
+        ImageFormat::Png => {
+            let mut png = Png::from_bytes(bytes.into()).map_err(|e| e.to_string())?;
+            for chunk in [*b"eXIf", *b"tEXt", *b"zTXt", *b"iTXt", *b"tIME"] {
+                png.remove_chunks_by_type(chunk);
+            }
+            Ok(png.encoder().bytes().to_vec())
+        }
+        ImageFormat::WebP => {
+            let mut webp = WebP::from_bytes(bytes.into()).map_err(|e| e.to_string())?;
+            webp.remove_chunks_by_id(CHUNK_XMP);
+            webp.set_exif(None);
+            Ok(webp.encoder().bytes().to_vec())
+        }
+        _ => Ok(bytes),
+    }
+}
+
+// Encoders only take 8-bit RGB(A)
+fn eight_bit(image: &DynamicImage, keep_alpha: bool) -> DynamicImage {
+    if keep_alpha && image.color().has_alpha() {
+        DynamicImage::ImageRgba8(image.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(image.to_rgb8())
+    }
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    match format {
+        ImageFormat::Jpeg => eight_bit(image, false)
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut bytes, quality)),
+        ImageFormat::WebP => {
+            eight_bit(image, true).write_with_encoder(WebPEncoder::new_lossless(&mut bytes))
+        }
+        ImageFormat::Avif => eight_bit(image, true).write_with_encoder(
+            AvifEncoder::new_with_speed_quality(&mut bytes, AVIF_SPEED, quality),
+        ),
+        other => image.write_to(Cursor::new(&mut bytes), other),
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(bytes)
+}
+
+fn write_variant(
+    images_folder: &Path,
+    image_name: &str,
+    file_stem: &str,
+    image: &DynamicImage,
+    format: ImageFormat,
+    quality: u8,
+) -> Result<ImageVariant, String> {
+    let extension = format.extensions_str()[0];
+    let file_name = format!("{}.{}", file_stem, extension);
+    let bytes = encode(image, format, quality)?;
+    std::fs::write(
+        variants_folder(images_folder, image_name).join(&file_name),
+        bytes,
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(ImageVariant {
+        path: format!("variants/{}/{}", image_name, file_name),
+        width: image.width(),
+        height: image.height(),
+        format: extension.to_string(),
+    })
+}
+
+fn ingest(
+    images_folder: &Path,
+    source: &Path,
+    options: &IngestImageOptions,
+) -> Result<IngestedImage, String> {
+    let extra_formats = match &options.formats {
+        Some(formats) => formats
+            .iter()
+            .map(|format| match format.as_str() {
+                "webp" => Ok(ImageFormat::WebP),
+                "avif" => Ok(ImageFormat::Avif),
+                other => Err(format!("Unsupported variant format: {}", other)),
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => vec![ImageFormat::WebP, ImageFormat::Avif],
+    };
+    let quality = options.quality.unwrap_or(DEFAULT_QUALITY).clamp(1, 100);
+
+    let bytes =
+        std::fs::read(source).map_err(|e| format!("Could not read {}: {}", source.display(), e))?;
+
+    let reader = ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+    let format = match reader.format() {
+        Some(
+            format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Gif),
+        ) => format,
+        _ => return Err("Only JPEG, PNG, WebP and GIF images can be ingested".to_string()),
+    };
+
+    let mut decoder = reader.into_decoder().map_err(|e| e.to_string())?;
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    image.apply_orientation(orientation);
+
+    // Rotated images have to be re-encoded since the EXIF orientation tag is dropped
+    let original = if orientation == Orientation::NoTransforms {
+        strip_metadata(format, bytes)?
+    } else {
+        encode(&image, format, quality)?
+    };
+
+    std::fs::create_dir_all(images_folder).map_err(|e| e.to_string())?;
+    let image_name = available_name(images_folder, source, format);
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(images_folder.join(&image_name))
+        .and_then(|mut file| file.write_all(&original))
+        .map_err(|e| e.to_string())?;
+
+    std::fs::create_dir_all(variants_folder(images_folder, &image_name))
+        .map_err(|e| e.to_string())?;
+
+    // Resized copies keep the source format, except GIFs which become PNGs
+    let resized_format = match format {
+        ImageFormat::Gif => ImageFormat::Png,
+        other => other,
+    };
+
+    let mut widths: Vec<u32> = options
+        .widths
+        .clone()
+        .unwrap_or_else(|| DEFAULT_WIDTHS.to_vec())
+        .into_iter()
+        .filter(|width| *width > 0 && *width < image.width())
+        .collect();
+    widths.sort_unstable();
+    widths.dedup();
+
+    let mut resized_formats = vec![resized_format];
+    resized_formats.extend(
+        extra_formats
+            .iter()
+            .copied()
+            .filter(|variant_format| *variant_format != resized_format),
+    );
+
+    let mut variants = Vec::new();
+    for width in widths {
+        let resized = image.resize(width, u32::MAX, FilterType::Lanczos3);
+        for variant_format in &resized_formats {
+            variants.push(write_variant(
+                images_folder,
+                &image_name,
+                &format!("{}w", width),
+                &resized,
+                *variant_format,
+                quality,
+            )?);
+        }
+    }
+
+    // Full size copies in the modern formats
+    for variant_format in &extra_formats {
+        if *variant_format != format {
+            let stem = format!("{}w", image.width());
+            variants.push(write_variant(
+                images_folder,
+                &image_name,
+                &stem,
+                &image,
+                *variant_format,
+                quality,
+            )?);
+        }
+    }
+
+    let thumbnail_size = options.thumbnail_size.unwrap_or(DEFAULT_THUMBNAIL_SIZE);
+    if thumbnail_size > 0 {
+        let thumbnail = image.resize_to_fill(thumbnail_size, thumbnail_size, FilterType::Lanczos3);
+        variants.push(write_variant(
+            images_folder,
+            &image_name,
+            "thumbnail",
+            &thumbnail,
+            resized_format,
+            quality,
+        )?);
+    }
+
+    Ok(IngestedImage {
+        image_name,
+        width: image.width(),
+        height: image.height(),
+        variants,
+    })
+}
+
+// Copies an image into the images folder and generates its variants. The returned
+// `image_name` is what posts and projects store.
+#[tauri::command]
+pub async fn ingest_image(
+    app: AppHandle,
+    source_path: String,
+    options: Option<IngestImageOptions>,
+) -> Result<IngestedImage, String> {
+    let images_folder = load_current_profile(&app)
+        .await?
+        .blog_images_folder()
+        .ok_or("Blog images path not configured")?;
+    let options = options.unwrap_or_default();
+
+    tokio::task::spawn_blocking(move || ingest(&images_folder, Path::new(&source_path), &options))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageUsage {
+    pub entity_type: String, // "blog_post", "project", "author" or "translation"
+    pub entity_id: i32,
+    pub title: String,
+    pub inline: bool, // Embedded in the markdown rather than used as the hero image
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryImage {
+    pub image_name: String,
+    pub width: Option<u32>, // None when the format can't be read, e.g. SVG
+    pub height: Option<u32>,
+    pub size: u64,
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+    pub usage_count: usize,
+    pub usages: Vec<ImageUsage>,
+    pub metadata: Option<ImageMetadata>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ImageMetadata {
+    pub file_name: String,
+    pub alt_text: Option<String>,
+    pub caption: Option<String>,
+    pub credit: Option<String>,
+    pub license: Option<String>,
+    pub focal_x: Option<f32>, // 0 is the left edge, 1 the right
+    pub focal_y: Option<f32>, // 0 is the top edge, 1 the bottom
+}
+
+impl Validate for ImageMetadata {
+    fn rules(&self, v: &mut Validator) {
+        v.required("file_name", &self.file_name)
+            .file_name("file_name", Some(&self.file_name))
+            .max_length("alt_text", self.alt_text.as_deref(), 1000)
+            .max_length("caption", self.caption.as_deref(), 1000)
+            .max_length("credit", self.credit.as_deref(), 200)
+            .max_length("license", self.license.as_deref(), 200)
+            .between("focal_x", self.focal_x, 0.0, 1.0)
+            .between("focal_y", self.focal_y, 0.0, 1.0);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccessibilityIssue {
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub title: String,
+    pub image: String, // Image name, or the reference as written for inline images
+    pub inline: bool,
+    pub line: Option<usize>, // Markdown line of inline images
+    pub message: String,
+}
+
+// Every use of every image, keyed by image name. Inline uses are only found when the
+// blog folder is known.
+async fn image_usages(
+    conn: &mut PgConnection,
+    blog_folder: Option<&Path>,
+) -> Result<HashMap<String, Vec<ImageUsage>>, String> {
+    let mut usages: HashMap<String, Vec<ImageUsage>> = HashMap::new();
+
+    // UNION, so a post using one image as both hero and preview image counts once
+    let heroes = sqlx::query!(
+        r#"
+        SELECT 'blog_post' as "entity_type!", id as "id!", title as "title!", image_name as "image_name!"
+        FROM tadgh_blog.blog_posts
+        WHERE image_name IS NOT NULL AND image_name <> ''
+        UNION
+        SELECT 'project', id, title, image_name
+        FROM tadgh_blog.projects
+        WHERE image_name IS NOT NULL AND image_name <> ''
+        UNION
+        SELECT 'author', id, name, avatar_name
+        FROM tadgh_blog.authors
+        WHERE avatar_name IS NOT NULL AND avatar_name <> ''
+        UNION
+        SELECT 'blog_post', id, title, og_image
+        FROM tadgh_blog.blog_posts
+        WHERE og_image IS NOT NULL AND og_image <> ''
+        UNION
+        SELECT 'project', id, title, og_image
+        FROM tadgh_blog.projects
+        WHERE og_image IS NOT NULL AND og_image <> ''
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for hero in heroes {
+        usages.entry(hero.image_name).or_default().push(ImageUsage {
+            entity_type: hero.entity_type,
+            entity_id: hero.id,
+            title: hero.title,
+            inline: false,
+        });
+    }
+
+    if let Some(folder) = blog_folder {
+        let documents = sqlx::query!(
+            r#"
+            SELECT 'blog_post' as "entity_type!", id as "id!", title as "title!", file_name as "file_name!"
+            FROM tadgh_blog.blog_posts
+            UNION ALL
+            SELECT 'translation', id, title, file_name
+            FROM tadgh_blog.blog_post_translations
+            ORDER BY 1, 2
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for document in documents {
+            let markdown = match tokio::fs::read_to_string(folder.join(&document.file_name)).await {
+                Ok(markdown) => markdown,
+                Err(_) => continue,
+            };
+            let references = markdown_references(&markdown);
+            let names: BTreeSet<&str> = references
+                .images
+                .iter()
+                .filter_map(|image| image_file_name(&image.url))
+                .collect();
+            for name in names {
+                usages
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(ImageUsage {
+                        entity_type: document.entity_type.clone(),
+                        entity_id: document.id,
+                        title: document.title.clone(),
+                        inline: true,
+                    });
+            }
+        }
+    }
+
+    Ok(usages)
+}
+
+// Replaces `from` wherever it is not part of a longer path or file name
+fn replace_reference(text: &str, from: &str, to: &str) -> String {
+    let is_path_char = |c: char| c.is_alphanumeric() || "-_./%".contains(c);
+
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (index, _) in text.match_indices(from) {
+        let before = text[..index].chars().next_back();
+        let after = text[index + from.len()..].chars().next();
+        if before.is_some_and(is_path_char) || after.is_some_and(is_path_char) {
+            continue;
+        }
+        result.push_str(&text[last..index]);
+        result.push_str(to);
+        last = index + from.len();
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+// Points every image reference to `old_name` in the markdown at `new_name`, keeping
+// whatever path the reference used
+fn rename_references(markdown: &str, old_name: &str, new_name: &str) -> String {
+    let urls: BTreeSet<String> = markdown_references(markdown)
+        .images
+        .into_iter()
+        .map(|image| image.url)
+        .filter(|url| image_file_name(url) == Some(old_name))
+        .collect();
+
+    urls.iter().fold(markdown.to_string(), |text, url| {
+        let index = url.rfind(old_name).unwrap_or_default();
+        let new_url = format!(
+            "{}{}{}",
+            &url[..index],
+            new_name,
+            &url[index + old_name.len()..]
+        );
+        replace_reference(&text, url, &new_url)
+    })
+}
+
+#[tauri::command]
+pub async fn get_images(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<LibraryImage>, String> {
+    let profile = load_current_profile(&app).await?;
+    let images_folder = profile
+        .blog_images_folder()
+        .ok_or("Blog images path not configured")?;
+
+    let (mut usages, mut details) = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+        let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+        let usages = image_usages(&mut conn, profile.blog_folder().as_deref()).await?;
+        let details: HashMap<String, ImageMetadata> = sqlx::query_as!(
+            ImageMetadata,
+            r#"
+            SELECT file_name, alt_text, caption, credit, license, focal_x, focal_y
+            FROM tadgh_blog.images
+            "#
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|metadata| (metadata.file_name.clone(), metadata))
+        .collect();
+        (usages, details)
+    };
+
+    let mut images = Vec::new();
+    for image_name in list_files(&images_folder).await? {
+        let path = images_folder.join(&image_name);
+        let file = tokio::fs::metadata(&path)
+            .await
+            .map_err(|e| e.to_string())?;
+        let dimensions = image::image_dimensions(&path).ok();
+        let usages = usages.remove(&image_name).unwrap_or_default();
+
+        images.push(LibraryImage {
+            width: dimensions.map(|(width, _)| width),
+            height: dimensions.map(|(_, height)| height),
+            size: file.len(),
+            modified: file.modified().ok().map(chrono::DateTime::from),
+            usage_count: usages.len(),
+            usages,
+            metadata: details.remove(&image_name),
+            image_name,
+        });
+    }
+
+    Ok(images)
+}
+
+// Renames an image and its variants, updating hero and link preview images, author
+// avatars and the markdown of posts and translations in one go. The extension is kept
+// and the new name is slugified like ingested images.
+#[tauri::command]
+pub async fn rename_image(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    watcher: State<'_, FolderWatcher>,
+    image_name: String,
+    new_name: String,
+) -> Result<String, String> {
+    let profile = load_current_profile(&app).await?;
+    let images_folder = profile
+        .blog_images_folder()
+        .ok_or("Blog images path not configured")?;
+    let blog_folder = profile
+        .blog_folder()
+        .ok_or("Blog folder path not configured")?;
+
+    let stem = slugify(
+        Path::new(&new_name)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default(),
+    );
+    if stem.is_empty() {
+        return Err("Invalid image name".to_string());
+    }
+    let new_name = match Path::new(&image_name).extension().and_then(|e| e.to_str()) {
+        Some(extension) => format!("{}.{}", stem, extension),
+        None => stem,
+    };
+
+    if new_name == image_name {
+        return Ok(new_name);
+    }
+    if !images_folder.join(&image_name).is_file() {
+        return Err(format!("{} not found in the images folder", image_name));
+    }
+    if images_folder.join(&new_name).exists() || variants_folder(&images_folder, &new_name).exists()
+    {
+        return Err(format!("{} already exists", new_name));
+    }
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    // Translations have no revisions or search index of their own, so they carry no post id
+    let documents = sqlx::query!(
+        r#"
+        SELECT id as "blog_post_id?", file_name as "file_name!" FROM tadgh_blog.blog_posts
+        UNION ALL
+        SELECT NULL, file_name FROM tadgh_blog.blog_post_translations
+        "#
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut rewritten = Vec::new();
+    for document in documents {
+        let path = blog_folder.join(&document.file_name);
+        let markdown = match tokio::fs::read_to_string(&path).await {
+            Ok(markdown) => markdown,
+            Err(_) => continue,
+        };
+        let updated = rename_references(&markdown, &image_name, &new_name);
+        if updated != markdown {
+            if let Some(id) = document.blog_post_id {
+                record_blog_post_revision(&mut tx, id, Some(&markdown), true).await?;
+            }
+            rewritten.push((document.blog_post_id, path, markdown, updated));
+        }
+    }
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.blog_posts SET image_name = $2 WHERE image_name = $1",
+        image_name,
+        new_name
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.projects SET image_name = $2 WHERE image_name = $1",
+        image_name,
+        new_name
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.blog_posts SET og_image = $2 WHERE og_image = $1",
+        image_name,
+        new_name
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.projects SET og_image = $2 WHERE og_image = $1",
+        image_name,
+        new_name
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.authors SET avatar_name = $2 WHERE avatar_name = $1",
+        image_name,
+        new_name
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.images SET file_name = $2 WHERE file_name = $1",
+        image_name,
+        new_name
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for (id, _, _, body) in &rewritten {
+        if let Some(id) = *id {
+            record_blog_post_revision(&mut tx, id, Some(body), false).await?;
+            index_blog_body(&mut tx, id, Some(body)).await?;
+        }
+    }
+
+    // Files are moved before the commit and put back if anything after that fails, so
+    // the folder and the database end up agreeing either way
+    move_image_files(&images_folder, &image_name, &new_name).await?;
+
+    // The rewritten posts and translations aren't outside edits, so the folder watcher
+    // shouldn't flag them
+    let _writing: Vec<_> = rewritten
+        .iter()
+        .filter_map(|(_, path, _, _)| path.file_name().and_then(|name| name.to_str()))
+        .map(|name| watcher.expect_write(name))
+        .collect();
+    for (written, (_, path, _, body)) in rewritten.iter().enumerate() {
+        if let Err(e) = tokio::fs::write(path, body).await {
+            undo_image_rename(
+                &images_folder,
+                &image_name,
+                &new_name,
+                &rewritten[..written],
+            )
+            .await;
+            return Err(e.to_string());
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        undo_image_rename(&images_folder, &image_name, &new_name, &rewritten).await;
+        return Err(e.to_string());
+    }
+
+    Ok(new_name)
+}
+
+// Moves an image and its variants folder, putting the image back if the variants can't follow
+async fn move_image_files(images_folder: &Path, from: &str, to: &str) -> Result<(), String> {
+    tokio::fs::rename(images_folder.join(from), images_folder.join(to))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let old_variants = variants_folder(images_folder, from);
+    if old_variants.exists() {
+        if let Err(e) = tokio::fs::rename(&old_variants, variants_folder(images_folder, to)).await {
+            let _ = tokio::fs::rename(images_folder.join(to), images_folder.join(from)).await;
+            return Err(e.to_string());
+        }
+    }
+    Ok(())
+}
+
+// Best effort, the error that got us here is the one worth reporting
+async fn undo_image_rename(
+    images_folder: &Path,
+    image_name: &str,
+    new_name: &str,
+    written: &[(Option<i32>, PathBuf, String, String)],
+) {
+    for (_, path, original, _) in written {
+        let _ = tokio::fs::write(path, original).await;
+    }
+    let _ = move_image_files(images_folder, new_name, image_name).await;
+}
+
+// Refuses to delete an image that is still in use unless `force` is set, in which
+// case the uses left pointing at the missing image are returned
+#[tauri::command]
+pub async fn delete_image(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    image_name: String,
+    force: Option<bool>,
+) -> Result<Vec<ImageUsage>, String> {
+    let profile = load_current_profile(&app).await?;
+    let images_folder = profile
+        .blog_images_folder()
+        .ok_or("Blog images path not configured")?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let usages = image_usages(&mut conn, profile.blog_folder().as_deref())
+        .await?
+        .remove(&image_name)
+        .unwrap_or_default();
+
+    if !usages.is_empty() && !force.unwrap_or(false) {
+        let titles: BTreeSet<&str> = usages.iter().map(|usage| usage.title.as_str()).collect();
+        return Err(format!(
+            "{} is still used by {}",
+            image_name,
+            titles.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    tokio::fs::remove_file(images_folder.join(&image_name))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let variants = variants_folder(&images_folder, &image_name);
+    if variants.exists() {
+        tokio::fs::remove_dir_all(&variants)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.images WHERE file_name = $1",
+        image_name
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(usages)
+}
+
+#[tauri::command]
+pub async fn get_image_metadata(
+    state: State<'_, Mutex<AppState>>,
+    image_name: String,
+) -> Result<ImageMetadata, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let metadata = sqlx::query_as!(
+        ImageMetadata,
+        r#"
+        SELECT file_name, alt_text, caption, credit, license, focal_x, focal_y
+        FROM tadgh_blog.images
+        WHERE file_name = $1
+        "#,
+        image_name
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Images without a row yet just have no details
+    Ok(metadata.unwrap_or(ImageMetadata {
+        file_name: image_name,
+        alt_text: None,
+        caption: None,
+        credit: None,
+        license: None,
+        focal_x: None,
+        focal_y: None,
+    }))
+}
+
+#[tauri::command]
+pub async fn update_image_metadata(
+    state: State<'_, Mutex<AppState>>,
+    metadata: ImageMetadata,
+) -> Result<ImageMetadata, String> {
+    metadata.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        ImageMetadata,
+        r#"
+        INSERT INTO tadgh_blog.images (file_name, alt_text, caption, credit, license, focal_x, focal_y)
+        VALUES ($1, NULLIF(TRIM($2), ''), NULLIF(TRIM($3), ''), NULLIF(TRIM($4), ''), NULLIF(TRIM($5), ''), $6, $7)
+        ON CONFLICT (file_name) DO UPDATE
+        SET alt_text = EXCLUDED.alt_text,
+            caption = EXCLUDED.caption,
+            credit = EXCLUDED.credit,
+            license = EXCLUDED.license,
+            focal_x = EXCLUDED.focal_x,
+            focal_y = EXCLUDED.focal_y,
+            updated_at = NOW()
+        RETURNING file_name, alt_text, caption, credit, license, focal_x, focal_y
+        "#,
+        metadata.file_name,
+        metadata.alt_text,
+        metadata.caption,
+        metadata.credit,
+        metadata.license,
+        metadata.focal_x,
+        metadata.focal_y
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Flags hero images of posts and projects with no alt text in the images table, and
+// images embedded in post markdown with empty alt text
+#[tauri::command]
+pub async fn lint_image_accessibility(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: Option<i32>,
+) -> Result<Vec<AccessibilityIssue>, String> {
+    let blog_folder = load_current_profile(&app).await?.blog_folder();
+
+    let (heroes, posts) = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+        let heroes = sqlx::query!(
+            r#"
+            SELECT h.entity_type as "entity_type!", h.id as "id!", h.title as "title!", h.image_name as "image_name!"
+            FROM (
+                SELECT 'blog_post' as entity_type, id, title, image_name
+                FROM tadgh_blog.blog_posts
+                WHERE $1::int IS NULL OR id = $1
+                UNION ALL
+                SELECT 'project', id, title, image_name
+                FROM tadgh_blog.projects
+                WHERE $1::int IS NULL
+            ) h
+            LEFT JOIN tadgh_blog.images i ON i.file_name = h.image_name
+            WHERE h.image_name IS NOT NULL AND h.image_name <> ''
+              AND i.alt_text IS NULL
+            ORDER BY h.entity_type, h.id
+            "#,
+            blog_post_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let posts = sqlx::query!(
+            r#"
+            SELECT id, title, file_name
+            FROM tadgh_blog.blog_posts
+            WHERE $1::int IS NULL OR id = $1
+            ORDER BY id ASC
+            "#,
+            blog_post_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        (heroes, posts)
+    };
+
+    let mut issues: Vec<AccessibilityIssue> = heroes
+        .into_iter()
+        .map(|hero| AccessibilityIssue {
+            message: format!("Hero image {} has no alt text", hero.image_name),
+            entity_type: hero.entity_type,
+            entity_id: hero.id,
+            title: hero.title,
+            image: hero.image_name,
+            inline: false,
+            line: None,
+        })
+        .collect();
+
+    if let Some(folder) = blog_folder {
+        for post in posts {
+            let markdown = match tokio::fs::read_to_string(folder.join(&post.file_name)).await {
+                Ok(markdown) => markdown,
+                Err(_) => continue,
+            };
+            for image in markdown_references(&markdown).images {
+                if image.alt.trim().is_empty() {
+                    issues.push(AccessibilityIssue {
+                        entity_type: "blog_post".to_string(),
+                        entity_id: post.id,
+                        title: post.title.clone(),
+                        message: format!("Image {} has no alt text", image.url),
+                        image: image.url,
+                        inline: true,
+                        line: Some(image.line),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(issues)
+}
@@ -0,0 +1,421 @@
+use serde::{Deserialize, Serialize};
+use similar::get_close_matches;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::preview::{image_file_name, markdown_references};
+use crate::settings::load_current_profile;
+use crate::slugs::permalink;
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    // "missing_file", "missing_image", "missing_inline_image", "orphaned_image",
+    // "untracked_file", "broken_link" or "redirected_link"
+    pub kind: String,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i32>,
+    pub path: String, // File name or link target the issue is about
+    pub message: String,
+    pub suggestion: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub posts_checked: usize,
+    pub projects_checked: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+// Everything an internal link in a post can point at
+struct LinkTargets {
+    live: HashSet<String>,
+    redirects: HashMap<String, String>,
+    file_names: HashSet<String>,
+}
+
+impl LinkTargets {
+    // Returns the issue kind and a suggested fix when the link does not resolve
+    fn check(&self, url: &str) -> Option<(&'static str, String)> {
+        if url.contains(':') || url.starts_with("//") || url.starts_with('#') {
+            return None; // External, mailto: or in-page anchor
+        }
+
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let path = if path.len() > 1 {
+            path.trim_end_matches('/')
+        } else {
+            path
+        };
+
+        if path.ends_with(".md") {
+            let file_name = Path::new(path).file_name()?.to_str()?;
+            return (!self.file_names.contains(file_name)).then(|| {
+                (
+                    "broken_link",
+                    "Link to an existing post's file or remove the link".to_string(),
+                )
+            });
+        }
+
+        let internal = ["/blog/", "/projects/", "/tags/", "/categories/"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix));
+        if !internal || self.live.contains(path) {
+            return None;
+        }
+
+        if let Some(target) = self.redirects.get(path) {
+            return Some(("redirected_link", format!("Link directly to {}", target)));
+        }
+
+        let candidates: Vec<&str> = self.live.iter().map(String::as_str).collect();
+        let suggestion = match get_close_matches(path, &candidates, 1, 0.7).first() {
+            Some(closest) => format!("Did you mean {}?", closest),
+            None => "Point the link at an existing page or remove it".to_string(),
+        };
+        Some(("broken_link", suggestion))
+    }
+}
+
+// Top-level file names only, generated variants live in subfolders
+pub async fn list_files(folder: &Path) -> Result<Vec<String>, String> {
+    let mut entries = tokio::fs::read_dir(folder)
+        .await
+        .map_err(|e| format!("Could not read {}: {}", folder.display(), e))?;
+
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+        let is_file = entry
+            .file_type()
+            .await
+            .map_err(|e| e.to_string())?
+            .is_file();
+        if let (true, Some(name)) = (is_file, entry.file_name().to_str()) {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+fn issue(
+    kind: &str,
+    entity: Option<(&str, i32)>,
+    path: &str,
+    message: String,
+    suggestion: String,
+) -> IntegrityIssue {
+    IntegrityIssue {
+        kind: kind.to_string(),
+        entity_type: entity.map(|(entity_type, _)| entity_type.to_string()),
+        entity_id: entity.map(|(_, id)| id),
+        path: path.to_string(),
+        message,
+        suggestion,
+    }
+}
+
+// Checks files, images and internal links of one post and its translations, or of the
+// whole site when no id is given. Orphaned images and untracked markdown files are only reported site-wide.
+#[tauri::command]
+pub async fn check_content_integrity(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: Option<i32>,
+) -> Result<IntegrityReport, String> {
+    let profile = load_current_profile(&app).await?;
+    let blog_folder = profile
+        .blog_folder()
+        .ok_or("Blog folder path not configured")?;
+    let images_folder = profile
+        .blog_images_folder()
+        .ok_or("Blog images path not configured")?;
+
+    let (posts, translations, projects, authors, targets) = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+        let posts = sqlx::query!(
+            r#"
+            SELECT id, title, file_name, image_name, og_image
+            FROM tadgh_blog.blog_posts
+            WHERE $1::int IS NULL OR id = $1
+            ORDER BY id ASC
+            "#,
+            blog_post_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if blog_post_id.is_some() && posts.is_empty() {
+            return Err("Blog post not found".to_string());
+        }
+
+        let projects = sqlx::query!(
+            r#"
+            SELECT id, title, image_name, og_image
+            FROM tadgh_blog.projects
+            WHERE $1::int IS NULL
+            ORDER BY id ASC
+            "#,
+            blog_post_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        // Translations are checked along with the post they translate
+        let translations = sqlx::query!(
+            r#"
+            SELECT id, title, file_name
+            FROM tadgh_blog.blog_post_translations
+            WHERE $1::int IS NULL OR blog_post_id = $1
+            ORDER BY id ASC
+            "#,
+            blog_post_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let authors = sqlx::query!(
+            r#"
+            SELECT id, name, avatar_name as "avatar_name!"
+            FROM tadgh_blog.authors
+            WHERE $1::int IS NULL AND avatar_name IS NOT NULL AND avatar_name <> ''
+            ORDER BY id ASC
+            "#,
+            blog_post_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let slugs = sqlx::query!(
+            r#"
+            SELECT 'blog_post' as "entity_type!", slug FROM tadgh_blog.blog_posts
+            UNION ALL SELECT 'project', slug FROM tadgh_blog.projects
+            UNION ALL SELECT 'tag', slug FROM tadgh_blog.tags
+            UNION ALL SELECT 'category', slug FROM tadgh_blog.categories
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let redirects = sqlx::query!("SELECT from_path, to_path FROM tadgh_blog.redirects")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let file_names = sqlx::query_scalar!(
+            r#"
+            SELECT file_name as "file_name!" FROM tadgh_blog.blog_posts
+            UNION SELECT file_name FROM tadgh_blog.blog_post_translations
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let targets = LinkTargets {
+            live: slugs
+                .iter()
+                .filter_map(|row| Some(permalink(&row.entity_type, row.slug.as_deref()?)))
+                .collect(),
+            redirects: redirects
+                .into_iter()
+                .map(|row| (row.from_path, row.to_path))
+                .collect(),
+            file_names: file_names.into_iter().collect(),
+        };
+
+        (posts, translations, projects, authors, targets)
+    };
+
+    let image_files: HashSet<String> = list_files(&images_folder).await?.into_iter().collect();
+    let mut referenced_images = HashSet::new();
+    let mut issues = Vec::new();
+    // Markdown files to check: (entity, what to call it, title, file name)
+    let mut documents = Vec::new();
+
+    for post in &posts {
+        let entity = Some(("blog_post", post.id));
+
+        if let Some(image) = post.image_name.as_deref().filter(|name| !name.is_empty()) {
+            referenced_images.insert(image.to_string());
+            if !image_files.contains(image) {
+                issues.push(issue(
+                    "missing_image",
+                    entity,
+                    image,
+                    format!(
+                        "Hero image of \"{}\" is not in the images folder",
+                        post.title
+                    ),
+                    "Add the image to the images folder or clear the post's image".to_string(),
+                ));
+            }
+        }
+
+        if let Some(image) = post.og_image.as_deref().filter(|name| !name.is_empty()) {
+            referenced_images.insert(image.to_string());
+            if !image_files.contains(image) {
+                issues.push(issue(
+                    "missing_image",
+                    entity,
+                    image,
+                    format!(
+                        "Link preview image of \"{}\" is not in the images folder",
+                        post.title
+                    ),
+                    "Add the image to the images folder or clear the post's preview image"
+                        .to_string(),
+                ));
+            }
+        }
+
+        documents.push((entity, "post", post.title.as_str(), post.file_name.as_str()));
+    }
+
+    for translation in &translations {
+        documents.push((
+            Some(("translation", translation.id)),
+            "translation",
+            translation.title.as_str(),
+            translation.file_name.as_str(),
+        ));
+    }
+
+    for (entity, noun, title, file_name) in documents {
+        let markdown = match tokio::fs::read_to_string(blog_folder.join(file_name)).await {
+            Ok(markdown) => markdown,
+            Err(_) => {
+                issues.push(issue(
+                    "missing_file",
+                    entity,
+                    file_name,
+                    format!("Markdown file of \"{}\" is not in the blog folder", title),
+                    format!("Restore the file or update the {}'s file name", noun),
+                ));
+                continue;
+            }
+        };
+
+        let references = markdown_references(&markdown);
+        for image in &references.images {
+            if let Some(name) = image_file_name(&image.url) {
+                referenced_images.insert(name.to_string());
+                if !image_files.contains(name) {
+                    issues.push(issue(
+                        "missing_inline_image",
+                        entity,
+                        &image.url,
+                        format!("\"{}\" embeds an image that does not exist", title),
+                        "Add the image to the images folder or remove the reference".to_string(),
+                    ));
+                }
+            }
+        }
+
+        for url in &references.links {
+            if let Some((kind, suggestion)) = targets.check(url) {
+                issues.push(issue(
+                    kind,
+                    entity,
+                    url,
+                    format!("\"{}\" links to {}", title, url),
+                    suggestion,
+                ));
+            }
+        }
+    }
+
+    for project in &projects {
+        if let Some(image) = project
+            .image_name
+            .as_deref()
+            .filter(|name| !name.is_empty())
+        {
+            referenced_images.insert(image.to_string());
+            if !image_files.contains(image) {
+                issues.push(issue(
+                    "missing_image",
+                    Some(("project", project.id)),
+                    image,
+                    format!(
+                        "Image of project \"{}\" is not in the images folder",
+                        project.title
+                    ),
+                    "Add the image to the images folder or clear the project's image".to_string(),
+                ));
+            }
+        }
+
+        if let Some(image) = project.og_image.as_deref().filter(|name| !name.is_empty()) {
+            referenced_images.insert(image.to_string());
+            if !image_files.contains(image) {
+                issues.push(issue(
+                    "missing_image",
+                    Some(("project", project.id)),
+                    image,
+                    format!(
+                        "Link preview image of project \"{}\" is not in the images folder",
+                        project.title
+                    ),
+                    "Add the image to the images folder or clear the project's preview image"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+
+    for author in &authors {
+        referenced_images.insert(author.avatar_name.clone());
+        if !image_files.contains(&author.avatar_name) {
+            issues.push(issue(
+                "missing_image",
+                Some(("author", author.id)),
+                &author.avatar_name,
+                format!("Avatar of \"{}\" is not in the images folder", author.name),
+                "Add the image to the images folder or clear the author's avatar".to_string(),
+            ));
+        }
+    }
+
+    if blog_post_id.is_none() {
+        let mut orphaned: Vec<&String> = image_files.difference(&referenced_images).collect();
+        orphaned.sort();
+        for image in orphaned {
+            issues.push(issue(
+                "orphaned_image",
+                None,
+                image,
+                format!("{} is not used by any post, project or author", image),
+                "Delete the image or attach it to a post, project or author".to_string(),
+            ));
+        }
+
+        for file_name in list_files(&blog_folder).await? {
+            if file_name.ends_with(".md") && !targets.file_names.contains(&file_name) {
+                issues.push(issue(
+                    "untracked_file",
+                    None,
+                    &file_name,
+                    format!("{} has no blog post", file_name),
+                    "Create a blog post for the file or delete it".to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(IntegrityReport {
+        posts_checked: posts.len(),
+        projects_checked: projects.len(),
+        issues,
+    })
+}
@@ -0,0 +1,357 @@
+use comrak::nodes::{NodeValue, Sourcepos};
+use comrak::{parse_document, Arena};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::preview::{markdown_options, node_text};
+use crate::settings::load_current_profile;
+use crate::AppState;
+
+const DEFAULT_MAX_PARAGRAPH_WORDS: usize = 150;
+
+// Every rule is on unless turned off
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LintRules {
+    pub heading_hierarchy: Option<bool>,
+    pub single_h1: Option<bool>,
+    pub long_paragraphs: Option<bool>,
+    pub max_paragraph_words: Option<usize>,
+    pub bare_urls: Option<bool>,
+    pub code_block_language: Option<bool>,
+    pub trailing_whitespace: Option<bool>,
+    pub image_alt_text: Option<bool>,
+}
+
+// Lines and columns are 1-based, columns count characters and the end is inclusive
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintDiagnostic {
+    pub rule: String,
+    pub severity: String, // "error" or "warning"
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostLint {
+    pub blog_post_id: i32,
+    pub title: String,
+    pub file_name: String,
+    pub published: bool,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<LintDiagnostic>,
+    pub read_error: Option<String>, // Set when the markdown file could not be read
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintReport {
+    pub posts_checked: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub posts: Vec<PostLint>, // Only posts with diagnostics, most errors first
+}
+
+// Converts comrak's byte positions to character positions on the source lines
+pub struct Lines<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(markdown: &'a str) -> Self {
+        Lines {
+            lines: markdown.lines().collect(),
+        }
+    }
+
+    pub fn get(&self, line: usize) -> &'a str {
+        self.lines.get(line.wrapping_sub(1)).copied().unwrap_or("")
+    }
+
+    pub fn column(&self, line: usize, byte_column: usize) -> usize {
+        let text = self.get(line);
+        let end = byte_column.saturating_sub(1).min(text.len());
+        let end = (0..=end)
+            .rev()
+            .find(|index| text.is_char_boundary(*index))
+            .unwrap_or(0);
+        text[..end].chars().count() + 1
+    }
+
+    fn diagnostic(
+        &self,
+        rule: &str,
+        severity: &str,
+        position: Sourcepos,
+        message: String,
+    ) -> LintDiagnostic {
+        LintDiagnostic {
+            rule: rule.to_string(),
+            severity: severity.to_string(),
+            line: position.start.line,
+            column: self.column(position.start.line, position.start.column),
+            end_line: position.end.line,
+            end_column: self.column(position.end.line, position.end.column),
+            message,
+        }
+    }
+}
+
+// Byte ranges of http(s) URLs in a piece of source text, without trailing punctuation
+pub fn find_urls(text: &str) -> Vec<(usize, usize)> {
+    let mut urls = Vec::new();
+    let mut search_from = 0;
+    while let Some(found) = ["https://", "http://"]
+        .iter()
+        .filter_map(|scheme| text[search_from..].find(scheme))
+        .min()
+    {
+        let start = search_from + found;
+        let length = text[start..]
+            .find(|c: char| c.is_whitespace() || "<>\"'`".contains(c))
+            .unwrap_or(text.len() - start);
+        let url = text[start..start + length].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+        urls.push((start, start + url.len()));
+        search_from = start + length;
+    }
+    urls
+}
+
+pub fn lint_markdown(markdown: &str, rules: &LintRules) -> Vec<LintDiagnostic> {
+    let enabled = |rule: Option<bool>| rule.unwrap_or(true);
+    let max_words = rules
+        .max_paragraph_words
+        .unwrap_or(DEFAULT_MAX_PARAGRAPH_WORDS)
+        .max(1);
+
+    // Without autolinks, bare URLs stay plain text and only real links become link nodes
+    let mut options = markdown_options();
+    options.extension.autolink = false;
+    let arena = Arena::new();
+    let root = parse_document(&arena, markdown, &options);
+    let lines = Lines::new(markdown);
+
+    let mut diagnostics = Vec::new();
+    let mut code_lines = Vec::new();
+    let mut h1_seen = false;
+    let mut previous_level = 1; // The post title is the page's H1, so H2 is a fine start
+
+    for node in root.descendants() {
+        let data = node.data();
+        let position = data.sourcepos;
+        match &data.value {
+            NodeValue::Heading(heading) => {
+                let level = heading.level;
+                if level == 1 && h1_seen && enabled(rules.single_h1) {
+                    diagnostics.push(lines.diagnostic(
+                        "single_h1",
+                        "error",
+                        position,
+                        "Only one H1 per post, use H2 for sections".to_string(),
+                    ));
+                }
+                if level > previous_level + 1 && enabled(rules.heading_hierarchy) {
+                    diagnostics.push(lines.diagnostic(
+                        "heading_hierarchy",
+                        "warning",
+                        position,
+                        format!(
+                            "Heading jumps from H{} to H{}, expected H{} or higher",
+                            previous_level,
+                            level,
+                            previous_level + 1
+                        ),
+                    ));
+                }
+                h1_seen |= level == 1;
+                previous_level = level;
+            }
+            NodeValue::Paragraph if enabled(rules.long_paragraphs) => {
+                let words = node_text(node).split_whitespace().count();
+                if words > max_words {
+                    diagnostics.push(lines.diagnostic(
+                        "long_paragraphs",
+                        "warning",
+                        position,
+                        format!(
+                            "Paragraph has {} words, consider splitting it (limit {})",
+                            words, max_words
+                        ),
+                    ));
+                }
+            }
+            NodeValue::CodeBlock(code) => {
+                code_lines.push(position.start.line..=position.end.line);
+                if !enabled(rules.code_block_language) {
+                    continue;
+                }
+                if !code.fenced {
+                    diagnostics.push(
+                        lines.diagnostic(
+                            "code_block_language",
+                            "warning",
+                            position,
+                            "Indented code block has no language, use a fenced block with one"
+                                .to_string(),
+                        ),
+                    );
+                } else if code.info.trim().is_empty() {
+                    diagnostics.push(lines.diagnostic(
+                        "code_block_language",
+                        "warning",
+                        position,
+                        "Code block has no language for highlighting".to_string(),
+                    ));
+                }
+            }
+            NodeValue::Image(link)
+                if enabled(rules.image_alt_text) && node_text(node).trim().is_empty() =>
+            {
+                diagnostics.push(lines.diagnostic(
+                    "image_alt_text",
+                    "error",
+                    position,
+                    format!("Image {} has no alt text", link.url),
+                ));
+            }
+            NodeValue::Text(_) if enabled(rules.bare_urls) => {
+                let in_link = node.ancestors().any(|ancestor| {
+                    matches!(
+                        ancestor.data().value,
+                        NodeValue::Link(_) | NodeValue::Image(_)
+                    )
+                });
+                if in_link || position.start.line != position.end.line {
+                    continue;
+                }
+                // Search the source rather than the parsed text so columns line up
+                let line = lines.get(position.start.line);
+                let start = position.start.column.saturating_sub(1);
+                let Some(source) = line.get(start..position.end.column.min(line.len())) else {
+                    continue;
+                };
+                for (url_start, url_end) in find_urls(source) {
+                    let mut url_position = position;
+                    url_position.start.column = start + url_start + 1;
+                    url_position.end.column = start + url_end;
+                    diagnostics.push(lines.diagnostic(
+                        "bare_urls",
+                        "warning",
+                        url_position,
+                        format!(
+                            "Bare URL, write it as <{}> or a [link]({})",
+                            &source[url_start..url_end],
+                            &source[url_start..url_end]
+                        ),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if enabled(rules.trailing_whitespace) {
+        for (index, line) in lines.lines.iter().enumerate() {
+            let number = index + 1;
+            let trimmed = line.trim_end_matches([' ', '\t']);
+            if trimmed.len() == line.len() || code_lines.iter().any(|range| range.contains(&number))
+            {
+                continue;
+            }
+            diagnostics.push(LintDiagnostic {
+                rule: "trailing_whitespace".to_string(),
+                severity: "warning".to_string(),
+                line: number,
+                column: trimmed.chars().count() + 1,
+                end_line: number,
+                end_column: line.chars().count(),
+                message: "Trailing whitespace, use a backslash for a hard line break".to_string(),
+            });
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    diagnostics
+}
+
+// Lints the markdown of one post, or of every post when no id is given
+#[tauri::command]
+pub async fn lint_blog_post(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: Option<i32>,
+    rules: Option<LintRules>,
+) -> Result<LintReport, String> {
+    let rules = rules.unwrap_or_default();
+    let blog_folder = load_current_profile(&app)
+        .await?
+        .blog_folder()
+        .ok_or("Blog folder path not configured")?;
+
+    let posts = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+        sqlx::query!(
+            r#"
+            SELECT id, title, file_name, published
+            FROM tadgh_blog.blog_posts
+            WHERE $1::int IS NULL OR id = $1
+            ORDER BY id ASC
+            "#,
+            blog_post_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+    };
+
+    if blog_post_id.is_some() && posts.is_empty() {
+        return Err("Blog post not found".to_string());
+    }
+
+    let posts_checked = posts.len();
+    let mut linted = Vec::new();
+    for post in posts {
+        let (diagnostics, read_error) =
+            match tokio::fs::read_to_string(blog_folder.join(&post.file_name)).await {
+                Ok(markdown) => (lint_markdown(&markdown, &rules), None),
+                Err(e) => (
+                    Vec::new(),
+                    Some(format!("Could not read {}: {}", post.file_name, e)),
+                ),
+            };
+        if diagnostics.is_empty() && read_error.is_none() {
+            continue;
+        }
+
+        let errors = diagnostics.iter().filter(|d| d.severity == "error").count();
+        linted.push(PostLint {
+            blog_post_id: post.id,
+            title: post.title,
+            file_name: post.file_name,
+            published: post.published,
+            errors,
+            warnings: diagnostics.len() - errors,
+            diagnostics,
+            read_error,
+        });
+    }
+
+    linted.sort_by(|a, b| {
+        b.errors
+            .cmp(&a.errors)
+            .then(b.warnings.cmp(&a.warnings))
+            .then(a.blog_post_id.cmp(&b.blog_post_id))
+    });
+
+    Ok(LintReport {
+        posts_checked,
+        errors: linted.iter().map(|post| post.errors).sum(),
+        warnings: linted.iter().map(|post| post.warnings).sum(),
+        posts: linted,
+    })
+}
@@ -0,0 +1,286 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgPool, PgRow, Postgres};
+use sqlx::{FromRow, QueryBuilder, Row};
+
+const MAX_PAGE_SIZE: i64 = 500;
+
+// Shared paging, sorting and filtering options accepted by the list commands.
+// Leaving `page_size` out returns every matching row.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ListOptions {
+    pub page: Option<i64>, // 1-based, ignored when a cursor is given
+    pub page_size: Option<i64>,
+    pub cursor: Option<String>, // `next_cursor` from the previous page
+    pub sort_by: Option<String>,
+    pub sort_direction: Option<String>, // "asc" or "desc"
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    pub tag_ids: Option<Vec<i32>>,
+    pub category_ids: Option<Vec<i32>>,
+    pub author_ids: Option<Vec<i32>>,
+    pub statuses: Option<Vec<String>>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: Option<i64>,
+    pub next_cursor: Option<String>,
+}
+
+// How the tags or authors of a listed row are found, e.g. blog_post_tags.blog_post_id = l.id
+pub struct LinkTable {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub owner: &'static str,
+}
+
+// Describes one list command. `source` is a full SELECT whose columns the
+// filters and sort fields refer to; it must expose an `id` column.
+pub struct Listing {
+    pub source: &'static str,
+    pub date_column: Option<&'static str>,
+    pub text_columns: &'static [&'static str],
+    pub tag_link: Option<LinkTable>,
+    pub author_link: Option<LinkTable>,
+    pub status_column: Option<&'static str>,
+    pub sort_fields: &'static [(&'static str, &'static str)], // (column, SQL type)
+    pub default_sort: (&'static str, bool),                   // (column, descending)
+}
+
+impl Listing {
+    fn sort(&self, options: &ListOptions) -> Result<(&'static str, &'static str, bool), String> {
+        let (column, sql_type) = match options.sort_by.as_deref() {
+            Some(requested) => *self
+                .sort_fields
+                .iter()
+                .find(|(column, _)| *column == requested)
+                .ok_or_else(|| format!("Cannot sort by {}", requested))?,
+            None => *self
+                .sort_fields
+                .iter()
+                .find(|(column, _)| *column == self.default_sort.0)
+                .ok_or("Invalid default sort")?,
+        };
+
+        let descending = match options.sort_direction.as_deref() {
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => return Err(format!("Unknown sort direction: {}", other)),
+            None if options.sort_by.is_none() => self.default_sort.1,
+            None => false,
+        };
+
+        Ok((column, sql_type, descending))
+    }
+
+    fn push_filters(
+        &self,
+        builder: &mut QueryBuilder<'_, Postgres>,
+        options: &ListOptions,
+        scope: Option<(&'static str, i32)>,
+    ) {
+        builder.push(" WHERE TRUE");
+
+        if let Some((column, id)) = scope {
+            builder.push(format!(" AND l.{} = ", column)).push_bind(id);
+        }
+
+        if let Some(date_column) = self.date_column {
+            if let Some(from) = options.date_from {
+                builder
+                    .push(format!(" AND CAST(l.{} AS date) >= ", date_column))
+                    .push_bind(from);
+            }
+            if let Some(to) = options.date_to {
+                builder
+                    .push(format!(" AND CAST(l.{} AS date) <= ", date_column))
+                    .push_bind(to);
+            }
+        }
+
+        if let Some(text) = options.text.as_deref().map(str::trim) {
+            if !text.is_empty() && !self.text_columns.is_empty() {
+                let pattern = format!(
+                    "%{}%",
+                    text.replace('\\', "\\\\")
+                        .replace('%', "\\%")
+                        .replace('_', "\\_")
+                );
+                builder.push(" AND (");
+                for (i, column) in self.text_columns.iter().enumerate() {
+                    if i > 0 {
+                        builder.push(" OR ");
+                    }
+                    builder
+                        .push(format!("l.{} ILIKE ", column))
+                        .push_bind(pattern.clone());
+                }
+                builder.push(")");
+            }
+        }
+
+        if let Some(link) = &self.tag_link {
+            if let Some(tag_ids) = options.tag_ids.as_ref().filter(|ids| !ids.is_empty()) {
+                builder
+                    .push(format!(
+                        " AND EXISTS (SELECT 1 FROM {} lt WHERE lt.{} = l.{} AND lt.tag_id = ANY(",
+                        link.table, link.column, link.owner
+                    ))
+                    .push_bind(tag_ids.clone())
+                    .push("))");
+            }
+            if let Some(category_ids) = options.category_ids.as_ref().filter(|ids| !ids.is_empty())
+            {
+                builder
+                    .push(format!(
+                        " AND EXISTS (SELECT 1 FROM {} lt \
+                         JOIN tadgh_blog.tag_categories tc ON tc.tag_id = lt.tag_id \
+                         WHERE lt.{} = l.{} AND tc.category_id = ANY(",
+                        link.table, link.column, link.owner
+                    ))
+                    .push_bind(category_ids.clone())
+                    .push("))");
+            }
+        }
+
+        if let Some(column) = self.status_column {
+            if let Some(statuses) = options.statuses.as_ref().filter(|s| !s.is_empty()) {
+                builder
+                    .push(format!(" AND l.{} = ANY(", column))
+                    .push_bind(statuses.clone())
+                    .push(")");
+            }
+        }
+
+        if let Some(link) = &self.author_link {
+            if let Some(author_ids) = options.author_ids.as_ref().filter(|ids| !ids.is_empty()) {
+                builder
+                    .push(format!(
+                        " AND EXISTS (SELECT 1 FROM {} la WHERE la.{} = l.{} AND la.author_id = ANY(",
+                        link.table, link.column, link.owner
+                    ))
+                    .push_bind(author_ids.clone())
+                    .push("))");
+            }
+        }
+    }
+
+    // Runs the count and page queries. `scope` limits rows to one parent,
+    // e.g. the views of a single post.
+    pub async fn fetch<T>(
+        &self,
+        pool: &PgPool,
+        options: Option<ListOptions>,
+        scope: Option<(&'static str, i32)>,
+    ) -> Result<Page<T>, String>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let options = options.unwrap_or_default();
+        let (sort_column, sort_type, descending) = self.sort(&options)?;
+        let page_size = options.page_size.map(|size| size.clamp(1, MAX_PAGE_SIZE));
+        let page = options.page.unwrap_or(1).max(1);
+
+        let mut count = QueryBuilder::new(format!("SELECT COUNT(*) FROM ({}) l", self.source));
+        self.push_filters(&mut count, &options, scope);
+        let total: i64 = count
+            .build_query_scalar()
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut query = QueryBuilder::new(format!(
+            "SELECT l.*, CAST(l.{} AS text) AS cursor_value FROM ({}) l",
+            sort_column, self.source
+        ));
+        self.push_filters(&mut query, &options, scope);
+
+        // Keyset pagination on (sort column, id), nulls always sort last
+        if let Some(cursor) = &options.cursor {
+            let (value, id) = decode_cursor(cursor)?;
+            let op = if descending { "<" } else { ">" };
+            match value {
+                Some(value) => {
+                    query
+                        .push(format!(" AND (l.{} {} CAST(", sort_column, op))
+                        .push_bind(value.clone())
+                        .push(format!(" AS {}) OR (l.{} = CAST(", sort_type, sort_column))
+                        .push_bind(value)
+                        .push(format!(" AS {}) AND l.id {} ", sort_type, op))
+                        .push_bind(id)
+                        .push(format!(") OR l.{} IS NULL)", sort_column));
+                }
+                None => {
+                    query
+                        .push(format!(" AND l.{} IS NULL AND l.id {} ", sort_column, op))
+                        .push_bind(id);
+                }
+            }
+        }
+
+        let direction = if descending { "DESC" } else { "ASC" };
+        query.push(format!(
+            " ORDER BY l.{} {} NULLS LAST, l.id {}",
+            sort_column, direction, direction
+        ));
+
+        if let Some(page_size) = page_size {
+            query.push(" LIMIT ").push_bind(page_size);
+            if options.cursor.is_none() {
+                query.push(" OFFSET ").push_bind((page - 1) * page_size);
+            }
+        }
+
+        let rows = query
+            .build()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let next_cursor = match (page_size, rows.last()) {
+            (Some(page_size), Some(last)) if rows.len() as i64 == page_size => {
+                let value: Option<String> =
+                    last.try_get("cursor_value").map_err(|e| e.to_string())?;
+                let id: i32 = last.try_get("id").map_err(|e| e.to_string())?;
+                Some(encode_cursor(value.as_deref(), id))
+            }
+            _ => None,
+        };
+
+        let items = rows
+            .iter()
+            .map(T::from_row)
+            .collect::<Result<Vec<T>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Page {
+            items,
+            total,
+            page: if options.cursor.is_some() { 0 } else { page },
+            page_size,
+            next_cursor,
+        })
+    }
+}
+
+// Cursors are `<id>:<sort value>`, or just `<id>` when the sort value was null
+fn encode_cursor(value: Option<&str>, id: i32) -> String {
+    match value {
+        Some(value) => format!("{}:{}", id, value),
+        None => id.to_string(),
+    }
+}
+
+fn decode_cursor(cursor: &str) -> Result<(Option<String>, i32), String> {
+    let (id, value) = match cursor.split_once(':') {
+        Some((id, value)) => (id, Some(value.to_string())),
+        None => (cursor, None),
+    };
+    let id = id.parse().map_err(|_| "Invalid cursor".to_string())?;
+    Ok((value, id))
+}
@@ -22,6 +22,52 @@ mod views;
 use views::*;
 mod analytics;
 use analytics::*;
+mod scheduler;
+use scheduler::*;
+mod revisions;
+use revisions::*;
+mod slugs;
+use slugs::*;
+mod search;
+use search::*;
+mod preview;
+use preview::*;
+mod integrity;
+use integrity::*;
+mod images;
+use images::*;
+mod export;
+use export::*;
+mod feeds;
+use feeds::*;
+mod sitemap;
+use sitemap::*;
+mod series;
+use series::*;
+mod related;
+use related::*;
+mod authors;
+use authors::*;
+mod seo;
+use seo::*;
+mod lint;
+use lint::*;
+mod spelling;
+use spelling::*;
+mod watcher;
+use watcher::*;
+mod comments;
+use comments::*;
+mod translations;
+use translations::*;
+mod schema;
+use schema::*;
+mod bulk;
+use bulk::*;
+
+// Shared by the modules above, no commands of their own
+mod listing;
+mod validation;
 
 #[derive(Deserialize)]
 struct ConnectionConfig {
@@ -42,6 +88,7 @@ impl AppState {
 #[tauri::command]
 async fn connect_db(
     state: State<'_, Mutex<AppState>>,
+    scheduler: State<'_, Scheduler>,
     connection_config: ConnectionConfig,
 ) -> Result<bool, String> {
     let pool = PgPoolOptions::new()
@@ -49,8 +96,12 @@ async fn connect_db(
         .connect(&connection_config.connection_string)
         .await
         .map_err(|e| e.to_string())?;
+
     let mut state = state.lock().await;
     state.pool = Some(pool);
+
+    // Pick up any schedules that came due while we were disconnected
+    scheduler.wake();
     Ok(true)
 }
 
@@ -72,8 +123,12 @@ async fn main() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(AppState::new())
+        .manage(Scheduler::default())
+        .manage(FolderWatcher::default())
         .setup(|app| {
             app.manage(Mutex::new(AppState::default()));
+            tauri::async_runtime::spawn(scheduler::run(app.handle().clone()));
+            tauri::async_runtime::spawn(watcher::run(app.handle().clone()));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -116,7 +171,95 @@ async fn main() {
             add_view_to_blog_post,
             add_multiple_views_to_blog_post,
             get_blog_post_views,
-            get_view_analytics
+            get_view_analytics,
+            schedule_publication,
+            get_scheduled_publications,
+            reschedule_publication,
+            cancel_publication,
+            get_revisions,
+            get_revision,
+            diff_revisions,
+            restore_revision,
+            generate_slug,
+            update_slug,
+            get_redirects,
+            create_redirect,
+            delete_redirect,
+            export_redirects,
+            search,
+            rebuild_search_index,
+            render_blog_post_preview,
+            check_content_integrity,
+            ingest_image,
+            get_images,
+            rename_image,
+            delete_image,
+            get_image_metadata,
+            update_image_metadata,
+            lint_image_accessibility,
+            export_site_data,
+            generate_feeds,
+            generate_sitemap,
+            get_series,
+            create_series,
+            update_series,
+            delete_series,
+            add_post_to_series,
+            remove_post_from_series,
+            reorder_series,
+            get_blog_post_series,
+            get_related_posts,
+            precompute_related_posts,
+            duplicate_blog_post,
+            duplicate_project,
+            create_blog_post_with_tags,
+            update_blog_post_with_tags,
+            create_project_with_tags,
+            update_project_with_tags,
+            bulk_update_blog_posts,
+            bulk_update_projects,
+            get_authors,
+            create_author,
+            update_author,
+            delete_author,
+            get_blog_post_authors,
+            set_blog_post_authors,
+            get_project_authors,
+            set_project_authors,
+            get_blog_post_seo,
+            update_blog_post_seo,
+            get_project_seo,
+            update_project_seo,
+            audit_seo,
+            lint_blog_post,
+            check_blog_post_spelling,
+            check_markdown_spelling,
+            get_custom_dictionary,
+            add_custom_dictionary_word,
+            remove_custom_dictionary_word,
+            create_blog_post_from_file,
+            get_file_flags,
+            dismiss_file_flag,
+            relink_blog_post_file,
+            get_comments,
+            approve_comments,
+            reject_comments,
+            mark_comments_as_spam,
+            reply_to_comment,
+            delete_comments,
+            get_comment_counts,
+            score_comments,
+            get_comment_blocklist,
+            add_comment_blocklist_term,
+            delete_comment_blocklist_term,
+            get_blog_post_translations,
+            set_blog_post_locale,
+            create_translation,
+            update_translation,
+            delete_translation,
+            get_missing_translations,
+            get_schema_migrations,
+            apply_schema_migrations
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,205 @@
+use comrak::nodes::NodeValue;
+use comrak::options::{Plugins, URLRewriter};
+use comrak::plugins::syntect::SyntectAdapter;
+use comrak::{markdown_to_html_with_plugins, parse_document, Arena, Node, Options};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::settings::load_current_profile;
+use crate::slugs::image_permalink;
+use crate::AppState;
+
+const CODE_THEME: &str = "base16-ocean.dark";
+
+// Same encoding as encodeURIComponent, used for asset protocol URLs
+fn encode_uri_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+// Matches convertFileSrc() on the frontend
+pub fn asset_url(path: &Path) -> String {
+    let encoded = encode_uri_component(&path.to_string_lossy());
+    if cfg!(windows) {
+        format!("http://asset.localhost/{}", encoded)
+    } else {
+        format!("asset://localhost/{}", encoded)
+    }
+}
+
+fn is_external(url: &str) -> bool {
+    url.contains("://") || url.starts_with("//") || url.starts_with("data:")
+}
+
+// Images are stored flat in the images folder, so a relative reference resolves to its
+// file name. External URLs resolve to nothing.
+pub fn image_file_name(url: &str) -> Option<&str> {
+    if is_external(url) {
+        return None;
+    }
+    let path = url.split(['?', '#']).next()?;
+    Path::new(path).file_name()?.to_str()
+}
+
+fn rewrite_image_url(images_folder: &Path, url: &str) -> String {
+    match image_file_name(url) {
+        Some(name) => asset_url(&images_folder.join(name)),
+        None => url.to_string(),
+    }
+}
+
+// GitHub flavoured markdown with footnotes and heading anchors
+pub fn markdown_options<'c>() -> Options<'c> {
+    let mut options = Options::default();
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    options.extension.tasklist = true;
+    options.extension.alerts = true;
+    options.extension.footnotes = true;
+    options.extension.header_id_prefix = Some(String::new());
+    options.render.github_pre_lang = true;
+    options
+}
+
+fn render(
+    markdown: &str,
+    image_url_rewriter: Option<Arc<dyn URLRewriter>>,
+    link_url_rewriter: Option<Arc<dyn URLRewriter>>,
+) -> String {
+    let mut options = markdown_options();
+    options.extension.image_url_rewriter = image_url_rewriter;
+    options.extension.link_url_rewriter = link_url_rewriter;
+
+    let highlighter = SyntectAdapter::new(Some(CODE_THEME));
+    let mut plugins = Plugins::default();
+    plugins.render.codefence_syntax_highlighter = Some(&highlighter);
+
+    markdown_to_html_with_plugins(markdown, &options, &plugins)
+}
+
+// Renders with highlighted code blocks. Relative image references are pointed at
+// `images_folder` when one is given.
+pub fn render_markdown(markdown: &str, images_folder: Option<PathBuf>) -> String {
+    let image_url_rewriter = images_folder.map(|folder| {
+        Arc::new(move |url: &str| rewrite_image_url(&folder, url)) as Arc<dyn URLRewriter>
+    });
+    render(markdown, image_url_rewriter, None)
+}
+
+// Renders for use outside the site's own pages, e.g. feed content, so images and
+// site-relative links become absolute URLs under `site_url`
+pub fn render_markdown_for_site(markdown: &str, site_url: &str) -> String {
+    let images_base = site_url.to_string();
+    let links_base = site_url.to_string();
+    render(
+        markdown,
+        Some(Arc::new(move |url: &str| match image_file_name(url) {
+            Some(name) => format!("{}{}", images_base, image_permalink(name)),
+            None => url.to_string(),
+        })),
+        Some(Arc::new(move |url: &str| {
+            if url.starts_with('/') && !url.starts_with("//") {
+                format!("{}{}", links_base, url)
+            } else {
+                url.to_string()
+            }
+        })),
+    )
+}
+
+#[derive(Debug)]
+pub struct MarkdownImage {
+    pub url: String,
+    pub alt: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct MarkdownReferences {
+    pub images: Vec<MarkdownImage>,
+    pub links: Vec<String>,
+}
+
+// Plain text inside a node, e.g. the alt text of an image
+pub fn node_text<'a>(node: Node<'a>) -> String {
+    let mut text = String::new();
+    for descendant in node.descendants() {
+        match &descendant.data().value {
+            NodeValue::Text(value) => text.push_str(value),
+            NodeValue::Code(code) => text.push_str(&code.literal),
+            _ => {}
+        }
+    }
+    text
+}
+
+// Image sources and link targets in document order
+pub fn markdown_references(markdown: &str) -> MarkdownReferences {
+    let arena = Arena::new();
+    let root = parse_document(&arena, markdown, &markdown_options());
+
+    let mut references = MarkdownReferences::default();
+    for node in root.descendants() {
+        let data = node.data();
+        match &data.value {
+            NodeValue::Image(link) => references.images.push(MarkdownImage {
+                url: link.url.clone(),
+                alt: node_text(node),
+                line: data.sourcepos.start.line,
+            }),
+            NodeValue::Link(link) => references.links.push(link.url.clone()),
+            _ => {}
+        }
+    }
+    references
+}
+
+#[tauri::command]
+pub async fn render_blog_post_preview(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+) -> Result<String, String> {
+    let file_name = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+        sqlx::query_scalar!(
+            "SELECT file_name FROM tadgh_blog.blog_posts WHERE id = $1",
+            blog_post_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Blog post not found")?
+    };
+
+    let profile = load_current_profile(&app).await?;
+    let folder = profile
+        .blog_folder()
+        .ok_or("Blog folder path not configured")?;
+    let markdown = tokio::fs::read_to_string(folder.join(&file_name))
+        .await
+        .map_err(|e| format!("Could not read {}: {}", file_name, e))?;
+
+    Ok(render_markdown(&markdown, profile.blog_images_folder()))
+}
@@ -1,9 +1,16 @@
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
-
-use tauri::State;
+use sqlx::PgConnection;
+use tauri::{AppHandle, State};
 use tokio::sync::Mutex;
 
+use crate::images::{copy_image, copy_image_metadata, remove_image_copy};
+use crate::listing::{LinkTable, ListOptions, Listing, Page};
+use crate::revisions::record_project_revision;
+use crate::settings::load_current_profile;
+use crate::slugs::{permalink, record_redirect, resolve_slug};
+use crate::tags::{replace_project_tags, resolve_tag_selection, TagSelection};
+use crate::validation::{Validate, Validator};
 use crate::AppState;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -16,31 +23,64 @@ pub struct Project {
     created: Option<NaiveDate>,
     released: bool,
     live: bool,
+    slug: Option<String>, // Generated from the title on create, kept as is on update when left out
+}
+
+impl Validate for Project {
+    fn rules(&self, v: &mut Validator) {
+        v.required("title", &self.title)
+            .max_length("title", Some(&self.title), 200)
+            .max_length("description", self.description.as_deref(), 1000)
+            .url("url", self.url.as_deref())
+            .max_length("url", self.url.as_deref(), 2048)
+            .date("created", self.created)
+            .file_name("image_name", self.image_name.as_deref())
+            .slug("slug", self.slug.as_deref());
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DuplicateProject {
+    title: Option<String>,    // Defaults to "<title> (copy)"
+    copy_image: Option<bool>, // Otherwise the copy shares the image
 }
 
+const PROJECT_LISTING: Listing = Listing {
+    source: r#"
+        SELECT id, title, description, image_name, url, created, released, live, slug
+        FROM tadgh_blog.projects
+    "#,
+    date_column: Some("created"),
+    text_columns: &["title", "description", "url"],
+    tag_link: Some(LinkTable {
+        table: "tadgh_blog.project_tags",
+        column: "project_id",
+        owner: "id",
+    }),
+    author_link: Some(LinkTable {
+        table: "tadgh_blog.project_authors",
+        column: "project_id",
+        owner: "id",
+    }),
+    status_column: None,
+    sort_fields: &[
+        ("created", "date"),
+        ("title", "text"),
+        ("released", "boolean"),
+        ("live", "boolean"),
+        ("id", "int4"),
+    ],
+    default_sort: ("created", true),
+};
+
 #[tauri::command]
-pub async fn get_projects(state: State<'_, Mutex<AppState>>) -> Result<Vec<Project>, String> {
+pub async fn get_projects(
+    state: State<'_, Mutex<AppState>>,
+    options: Option<ListOptions>,
+) -> Result<Page<Project>, String> {
     let state = state.lock().await;
     let pool = state.pool.as_ref().ok_or("Database not connected")?;
-    sqlx::query_as!(
-        Project,
-        r#"
-        SELECT
-            id,
-            title,
-            description,
-            image_name,
-            url,
-            created,
-            released,
-            live
-        FROM tadgh_blog.projects
-        ORDER BY created DESC
-        "#
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(|e| e.to_string())
+    PROJECT_LISTING.fetch(pool, options, None).await
 }
 #[tauri::command]
 pub async fn delete_project(
@@ -64,20 +104,23 @@ pub async fn delete_project(
     Ok(())
 }
 
-#[tauri::command]
-pub async fn create_project(
-    state: State<'_, Mutex<AppState>>,
-    project: Project,
-) -> Result<Project, String> {
-    let state = state.lock().await;
-    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+async fn insert_project(conn: &mut PgConnection, project: &Project) -> Result<Project, String> {
+    let slug = resolve_slug(
+        conn,
+        "project",
+        project.slug.as_deref(),
+        &project.title,
+        None,
+    )
+    .await?;
+
     sqlx::query_as!(
         Project,
         r#"
         INSERT INTO tadgh_blog.projects
-        (title, description, image_name, url, created, released, live)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
-        RETURNING id, title, description, image_name, url, created, released, live
+        (title, description, image_name, url, created, released, live, slug)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id, title, description, image_name, url, created, released, live, slug
         "#,
         project.title,
         project.description,
@@ -85,20 +128,45 @@ pub async fn create_project(
         project.url,
         project.created,
         project.released,
-        project.live
+        project.live,
+        slug
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-pub async fn update_project(
-    state: State<'_, Mutex<AppState>>,
-    project: Project,
+async fn apply_project_update(
+    conn: &mut PgConnection,
+    project_id: i32,
+    project: &Project,
 ) -> Result<(), String> {
-    let state = state.lock().await;
-    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let previous_slug = sqlx::query_scalar!(
+        "SELECT slug FROM tadgh_blog.projects WHERE id = $1 FOR UPDATE",
+        project_id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Project not found")?;
+
+    let slug = match project.slug.as_deref() {
+        Some(requested) if requested != previous_slug => {
+            resolve_slug(
+                conn,
+                "project",
+                Some(requested),
+                &project.title,
+                Some(project_id),
+            )
+            .await?
+        }
+        _ => previous_slug.clone(),
+    };
+
+    // Keep the pre-edit state around the first time a project is changed
+    record_project_revision(conn, project_id, true).await?;
+
     sqlx::query!(
         r#"
         UPDATE tadgh_blog.projects
@@ -108,8 +176,9 @@ pub async fn update_project(
             url = $4,
             created = $5,
             released = $6,
-            live = $7
-        WHERE id = $8
+            live = $7,
+            slug = $8
+        WHERE id = $9
         "#,
         project.title,
         project.description,
@@ -118,11 +187,226 @@ pub async fn update_project(
         project.created,
         project.released,
         project.live,
-        project.id
+        slug,
+        project_id
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await
     .map_err(|e| e.to_string())?;
 
+    if slug != previous_slug {
+        record_redirect(
+            conn,
+            &permalink("project", &previous_slug),
+            &permalink("project", &slug),
+            "project",
+            project_id,
+        )
+        .await?;
+    }
+
+    record_project_revision(conn, project_id, false).await?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn create_project(
+    state: State<'_, Mutex<AppState>>,
+    project: Project,
+) -> Result<Project, String> {
+    project.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let created = insert_project(&mut tx, &project).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+// Creates the project and attaches its tags in one transaction, creating tags given
+// by name that don't exist yet
+#[tauri::command]
+pub async fn create_project_with_tags(
+    state: State<'_, Mutex<AppState>>,
+    project: Project,
+    tags: TagSelection,
+) -> Result<Project, String> {
+    project.validate()?;
+    tags.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let created = insert_project(&mut tx, &project).await?;
+    let project_id = created.id.ok_or("Project was not created")?;
+    let tag_ids = resolve_tag_selection(&mut tx, &tags).await?;
+    replace_project_tags(&mut tx, project_id, &tag_ids).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn update_project(
+    state: State<'_, Mutex<AppState>>,
+    project: Project,
+) -> Result<(), String> {
+    project.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let project_id = project.id.ok_or("Project id is required")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    apply_project_update(&mut tx, project_id, &project).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
     Ok(())
 }
+
+// Updates the project and replaces its tag set in one transaction
+#[tauri::command]
+pub async fn update_project_with_tags(
+    state: State<'_, Mutex<AppState>>,
+    project: Project,
+    tags: TagSelection,
+) -> Result<(), String> {
+    project.validate()?;
+    tags.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let project_id = project.id.ok_or("Project id is required")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    apply_project_update(&mut tx, project_id, &project).await?;
+    let tag_ids = resolve_tag_selection(&mut tx, &tags).await?;
+    replace_project_tags(&mut tx, project_id, &tag_ids).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Copies a project and its tags as an unreleased draft, removing the copied image
+// again if the database work fails
+#[tauri::command]
+pub async fn duplicate_project(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    project_id: i32,
+    options: Option<DuplicateProject>,
+) -> Result<Project, String> {
+    let options = options.unwrap_or_default();
+    let images_folder = load_current_profile(&app)
+        .await
+        .ok()
+        .and_then(|profile| profile.blog_images_folder());
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    let original = sqlx::query_as!(
+        Project,
+        r#"
+        SELECT id as "id?", title, description, image_name, url, created, released, live, slug
+        FROM tadgh_blog.projects
+        WHERE id = $1
+        "#,
+        project_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Project not found")?;
+
+    let mut image_name = original.image_name.clone();
+    let mut copied_image = None;
+    if let (true, Some(name)) = (
+        options.copy_image.unwrap_or(false),
+        original
+            .image_name
+            .as_deref()
+            .filter(|name| !name.is_empty()),
+    ) {
+        let folder = images_folder
+            .as_ref()
+            .ok_or("Blog images folder not configured")?;
+        let new_name = copy_image(folder, name).await?;
+        image_name = Some(new_name.clone());
+        copied_image = Some(new_name);
+    }
+
+    let title = options
+        .title
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| format!("{} (copy)", original.title));
+
+    let result = async {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+        let slug = resolve_slug(&mut tx, "project", None, &title, None).await?;
+
+        let created = sqlx::query_as!(
+            Project,
+            r#"
+            INSERT INTO tadgh_blog.projects
+            (title, description, image_name, url, created, released, live, slug)
+            VALUES ($1, $2, $3, $4, $5, FALSE, FALSE, $6)
+            RETURNING id, title, description, image_name, url, created, released, live, slug
+            "#,
+            title,
+            original.description,
+            image_name,
+            original.url,
+            original.created,
+            slug
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tadgh_blog.project_tags (project_id, tag_id)
+            SELECT $1, tag_id FROM tadgh_blog.project_tags WHERE project_id = $2
+            "#,
+            created.id,
+            project_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO tadgh_blog.project_authors (project_id, author_id, position)
+            SELECT $1, author_id, position FROM tadgh_blog.project_authors WHERE project_id = $2
+            "#,
+            created.id,
+            project_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if let Some(new_name) = &copied_image {
+            copy_image_metadata(&mut tx, original.image_name.as_deref(), new_name).await?;
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        Ok::<Project, String>(created)
+    }
+    .await;
+
+    if let (Err(_), Some(folder), Some(name)) = (&result, &images_folder, &copied_image) {
+        remove_image_copy(folder, name).await;
+    }
+
+    result
+}
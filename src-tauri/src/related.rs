@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::settings::load_current_profile;
+use crate::slugs::permalink;
+use crate::AppState;
+
+// A shared tag counts for more than a shared category, since every tag in a category
+// implies the category as well
+const TAG_WEIGHT: f32 = 1.0;
+const CATEGORY_WEIGHT: f32 = 0.5;
+const TEXT_WEIGHT: f32 = 3.0;
+
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "that", "this", "with", "you", "are", "was", "but", "not", "have", "from",
+    "they", "your", "can", "will", "what", "when", "there", "which", "their", "them", "then",
+    "than", "into", "also", "just", "all", "any", "out", "our", "its", "has", "had", "one", "use",
+    "how", "more", "some", "would", "about", "been", "were", "http", "https",
+];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelatedPost {
+    pub id: i32,
+    pub title: String,
+    pub slug: String,
+    pub permalink: String,
+    pub score: f32,
+    pub shared_tags: Vec<String>,
+    pub shared_categories: Vec<String>,
+    pub text_similarity: Option<f32>, // Cosine similarity of the bodies, 0 to 1
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostRelations {
+    pub id: i32,
+    pub slug: String,
+    pub related: Vec<RelatedPost>,
+}
+
+struct Candidate {
+    id: i32,
+    title: String,
+    slug: String,
+    published: bool,
+    tags: HashSet<String>,
+    categories: HashSet<String>,
+    terms: HashMap<String, f32>, // Normalised TF-IDF weights, empty without text similarity
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() >= 3 && !word.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+// Replaces raw term counts with unit-length TF-IDF vectors so a dot product is the cosine
+fn weigh_terms(documents: Vec<HashMap<String, f32>>) -> Vec<HashMap<String, f32>> {
+    let mut document_frequency: HashMap<String, f32> = HashMap::new();
+    for terms in &documents {
+        for term in terms.keys() {
+            *document_frequency.entry(term.clone()).or_default() += 1.0;
+        }
+    }
+
+    let total = documents.len() as f32;
+    documents
+        .into_iter()
+        .map(|terms| {
+            let mut weighted: HashMap<String, f32> = terms
+                .into_iter()
+                .map(|(term, count)| {
+                    let idf = (total / document_frequency[&term]).ln() + 1.0;
+                    (term, (1.0 + count.ln()) * idf)
+                })
+                .collect();
+            let norm = weighted.values().map(|w| w * w).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                weighted.values_mut().for_each(|w| *w /= norm);
+            }
+            weighted
+        })
+        .collect()
+}
+
+fn cosine(a: &HashMap<String, f32>, b: &HashMap<String, f32>) -> f32 {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small
+        .iter()
+        .filter_map(|(term, weight)| large.get(term).map(|other| weight * other))
+        .sum()
+}
+
+fn sorted(values: impl Iterator<Item = String>) -> Vec<String> {
+    let mut values: Vec<String> = values.collect();
+    values.sort();
+    values
+}
+
+fn score_against(
+    target: &Candidate,
+    candidates: &[Candidate],
+    include_text: bool,
+    limit: usize,
+) -> Vec<RelatedPost> {
+    let mut related: Vec<RelatedPost> = candidates
+        .iter()
+        .filter(|candidate| candidate.id != target.id && candidate.published)
+        .filter_map(|candidate| {
+            let shared_tags = sorted(target.tags.intersection(&candidate.tags).cloned());
+            let shared_categories = sorted(
+                target
+                    .categories
+                    .intersection(&candidate.categories)
+                    .cloned(),
+            );
+            let text_similarity =
+                include_text.then(|| cosine(&target.terms, &candidate.terms).min(1.0));
+
+            let score = shared_tags.len() as f32 * TAG_WEIGHT
+                + shared_categories.len() as f32 * CATEGORY_WEIGHT
+                + text_similarity.unwrap_or(0.0) * TEXT_WEIGHT;
+            if score <= 0.0 {
+                return None;
+            }
+
+            Some(RelatedPost {
+                id: candidate.id,
+                title: candidate.title.clone(),
+                slug: candidate.slug.clone(),
+                permalink: permalink("blog_post", &candidate.slug),
+                score,
+                shared_tags,
+                shared_categories,
+                text_similarity,
+            })
+        })
+        .collect();
+
+    related.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.id.cmp(&b.id)));
+    related.truncate(limit);
+    related
+}
+
+// Published posts (plus `include_id`, which may be a draft) with their tags, categories
+// and, when asked for, body term weights read from the blog folder
+async fn load_candidates(
+    app: &AppHandle,
+    state: &State<'_, Mutex<AppState>>,
+    include_id: Option<i32>,
+    include_text: bool,
+) -> Result<Vec<Candidate>, String> {
+    let rows = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+        sqlx::query!(
+            r#"
+            SELECT
+                bp.id, bp.title, bp.slug, bp.file_name, bp.published, sd.body as "body?",
+                ARRAY(
+                    SELECT t.name FROM tadgh_blog.blog_post_tags bpt
+                    JOIN tadgh_blog.tags t ON t.id = bpt.tag_id
+                    WHERE bpt.blog_post_id = bp.id
+                ) as "tags!",
+                ARRAY(
+                    SELECT DISTINCT c.name FROM tadgh_blog.blog_post_tags bpt
+                    JOIN tadgh_blog.tag_categories tc ON tc.tag_id = bpt.tag_id
+                    JOIN tadgh_blog.categories c ON c.id = tc.category_id
+                    WHERE bpt.blog_post_id = bp.id
+                ) as "categories!"
+            FROM tadgh_blog.blog_posts bp
+            LEFT JOIN tadgh_blog.search_documents sd
+                ON sd.entity_type = 'blog_post' AND sd.entity_id = bp.id
+            WHERE bp.published OR bp.id = $1
+            ORDER BY bp.id
+            "#,
+            include_id
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+    };
+
+    // Prefer the file on disk, falling back to the last indexed body
+    let blog_folder = if include_text {
+        load_current_profile(app)
+            .await
+            .ok()
+            .and_then(|profile| profile.blog_folder())
+    } else {
+        None
+    };
+
+    let mut term_counts = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let mut counts: HashMap<String, f32> = HashMap::new();
+        if include_text {
+            let body = match &blog_folder {
+                Some(folder) => tokio::fs::read_to_string(folder.join(&row.file_name))
+                    .await
+                    .ok(),
+                None => None,
+            }
+            .or_else(|| row.body.clone())
+            .unwrap_or_default();
+
+            for term in tokenize(&format!("{} {}", row.title, body)) {
+                *counts.entry(term).or_default() += 1.0;
+            }
+        }
+        term_counts.push(counts);
+    }
+
+    Ok(rows
+        .into_iter()
+        .zip(weigh_terms(term_counts))
+        .map(|(row, terms)| Candidate {
+            id: row.id,
+            title: row.title,
+            slug: row.slug,
+            published: row.published,
+            tags: row.tags.into_iter().collect(),
+            categories: row.categories.into_iter().collect(),
+            terms,
+        })
+        .collect())
+}
+
+// Other published posts ranked by shared tags, shared categories and, with
+// `include_text`, similarity of the markdown bodies
+#[tauri::command]
+pub async fn get_related_posts(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_id: i32,
+    limit: Option<usize>,
+    include_text: Option<bool>,
+) -> Result<Vec<RelatedPost>, String> {
+    let include_text = include_text.unwrap_or(false);
+    let candidates = load_candidates(&app, &state, Some(blog_id), include_text).await?;
+
+    let target = candidates
+        .iter()
+        .find(|candidate| candidate.id == blog_id)
+        .ok_or("Blog post not found")?;
+
+    Ok(score_against(
+        target,
+        &candidates,
+        include_text,
+        limit.unwrap_or(5),
+    ))
+}
+
+// Related posts for every published post, optionally written as JSON for the static build
+#[tauri::command]
+pub async fn precompute_related_posts(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    limit: Option<usize>,
+    include_text: Option<bool>,
+    output_path: Option<String>,
+) -> Result<Vec<PostRelations>, String> {
+    let include_text = include_text.unwrap_or(false);
+    let limit = limit.unwrap_or(5);
+    let candidates = load_candidates(&app, &state, None, include_text).await?;
+
+    let relations: Vec<PostRelations> = candidates
+        .iter()
+        .map(|target| PostRelations {
+            id: target.id,
+            slug: target.slug.clone(),
+            related: score_against(target, &candidates, include_text, limit),
+        })
+        .collect();
+
+    if let Some(path) = output_path {
+        let json = serde_json::to_string_pretty(&relations).map_err(|e| e.to_string())?;
+        tokio::fs::write(&path, json)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(relations)
+}
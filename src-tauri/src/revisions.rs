@@ -0,0 +1,392 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use similar::{ChangeTag, TextDiff};
+use sqlx::PgConnection;
+use std::collections::BTreeSet;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::blog::read_blog_markdown;
+use crate::search::index_blog_body;
+use crate::settings::load_current_profile;
+use crate::slugs::{file_permalink, permalink, record_redirect};
+use crate::watcher::FolderWatcher;
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RevisionSummary {
+    pub id: i32,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub title: Option<String>,
+    pub has_body: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Revision {
+    pub id: i32,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub snapshot: Value,
+    pub body: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old_value: Value,
+    pub new_value: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineChange {
+    pub kind: String, // "equal", "insert" or "delete"
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevisionDiff {
+    pub from_id: i32,
+    pub to_id: i32,
+    pub fields: Vec<FieldChange>,
+    pub lines: Vec<LineChange>,
+}
+
+// Snapshots the current row. With `baseline_only` nothing is written once the post has
+// any history; otherwise nothing is written if the row and body match the latest revision.
+pub async fn record_blog_post_revision(
+    conn: &mut PgConnection,
+    blog_post_id: i32,
+    body: Option<&str>,
+    baseline_only: bool,
+) -> Result<(), String> {
+    sqlx::query!(
+        r#"
+        WITH latest AS (
+            SELECT snapshot, body
+            FROM tadgh_blog.revisions
+            WHERE entity_type = 'blog_post' AND entity_id = $1
+            ORDER BY id DESC
+            LIMIT 1
+        )
+        INSERT INTO tadgh_blog.revisions (entity_type, entity_id, snapshot, body)
+        SELECT 'blog_post', bp.id, to_jsonb(bp), $2
+        FROM tadgh_blog.blog_posts bp
+        WHERE bp.id = $1
+          AND NOT EXISTS (
+              SELECT 1 FROM latest
+              WHERE $3 OR (latest.snapshot = to_jsonb(bp) AND latest.body IS NOT DISTINCT FROM $2)
+          )
+        "#,
+        blog_post_id,
+        body,
+        baseline_only
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Same as `record_blog_post_revision`, projects have no markdown body
+pub async fn record_project_revision(
+    conn: &mut PgConnection,
+    project_id: i32,
+    baseline_only: bool,
+) -> Result<(), String> {
+    sqlx::query!(
+        r#"
+        WITH latest AS (
+            SELECT snapshot
+            FROM tadgh_blog.revisions
+            WHERE entity_type = 'project' AND entity_id = $1
+            ORDER BY id DESC
+            LIMIT 1
+        )
+        INSERT INTO tadgh_blog.revisions (entity_type, entity_id, snapshot)
+        SELECT 'project', p.id, to_jsonb(p)
+        FROM tadgh_blog.projects p
+        WHERE p.id = $1
+          AND NOT EXISTS (
+              SELECT 1 FROM latest
+              WHERE $2 OR latest.snapshot = to_jsonb(p)
+          )
+        "#,
+        project_id,
+        baseline_only
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+async fn fetch_revision(conn: &mut PgConnection, id: i32) -> Result<Revision, String> {
+    sqlx::query_as!(
+        Revision,
+        r#"
+        SELECT id, entity_type, entity_id, snapshot, body, created_at
+        FROM tadgh_blog.revisions
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Revision {} not found", id))
+}
+
+fn diff_fields(old: &Value, new: &Value) -> Vec<FieldChange> {
+    let empty = serde_json::Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+
+    let fields: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    fields
+        .into_iter()
+        .filter(|field| field.as_str() != "id")
+        .filter_map(|field| {
+            let old_value = old.get(field).cloned().unwrap_or(Value::Null);
+            let new_value = new.get(field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then(|| FieldChange {
+                field: field.clone(),
+                old_value,
+                new_value,
+            })
+        })
+        .collect()
+}
+
+fn diff_lines(old: &str, new: &str, context_lines: Option<usize>) -> Vec<LineChange> {
+    let diff = TextDiff::from_lines(old, new);
+    let ops = match context_lines {
+        Some(context) => diff.grouped_ops(context).into_iter().flatten().collect(),
+        None => diff.ops().to_vec(),
+    };
+
+    ops.iter()
+        .flat_map(|op| diff.iter_changes(op))
+        .map(|change| LineChange {
+            kind: match change.tag() {
+                ChangeTag::Equal => "equal",
+                ChangeTag::Insert => "insert",
+                ChangeTag::Delete => "delete",
+            }
+            .to_string(),
+            old_line: change.old_index().map(|i| i + 1),
+            new_line: change.new_index().map(|i| i + 1),
+            content: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_revisions(
+    state: State<'_, Mutex<AppState>>,
+    entity_type: String,
+    entity_id: i32,
+) -> Result<Vec<RevisionSummary>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        RevisionSummary,
+        r#"
+        SELECT
+            id,
+            entity_type,
+            entity_id,
+            snapshot->>'title' as title,
+            body IS NOT NULL as "has_body!",
+            created_at
+        FROM tadgh_blog.revisions
+        WHERE entity_type = $1 AND entity_id = $2
+        ORDER BY id DESC
+        "#,
+        entity_type,
+        entity_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_revision(state: State<'_, Mutex<AppState>>, id: i32) -> Result<Revision, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    fetch_revision(&mut conn, id).await
+}
+
+#[tauri::command]
+pub async fn diff_revisions(
+    state: State<'_, Mutex<AppState>>,
+    from_id: i32,
+    to_id: i32,
+    context_lines: Option<usize>,
+) -> Result<RevisionDiff, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let from = fetch_revision(&mut conn, from_id).await?;
+    let to = fetch_revision(&mut conn, to_id).await?;
+
+    if from.entity_type != to.entity_type || from.entity_id != to.entity_id {
+        return Err("Revisions belong to different entities".to_string());
+    }
+
+    Ok(RevisionDiff {
+        from_id,
+        to_id,
+        fields: diff_fields(&from.snapshot, &to.snapshot),
+        lines: diff_lines(
+            from.body.as_deref().unwrap_or_default(),
+            to.body.as_deref().unwrap_or_default(),
+            context_lines,
+        ),
+    })
+}
+
+#[tauri::command]
+pub async fn restore_revision(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    watcher: State<'_, FolderWatcher>,
+    id: i32,
+    restore_body: Option<bool>,
+) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let revision = fetch_revision(&mut tx, id).await?;
+
+    match revision.entity_type.as_str() {
+        "blog_post" => {
+            let current_file = sqlx::query_scalar!(
+                "SELECT file_name FROM tadgh_blog.blog_posts WHERE id = $1",
+                revision.entity_id
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Blog post no longer exists")?;
+
+            // Capture anything that changed since the last revision before overwriting it
+            let current_body = read_blog_markdown(&app, &current_file).await;
+            record_blog_post_revision(&mut tx, revision.entity_id, current_body.as_deref(), false)
+                .await?;
+
+            let restored = sqlx::query!(
+                r#"
+                UPDATE tadgh_blog.blog_posts bp
+                SET title = r.title,
+                    created = r.created,
+                    description = r.description,
+                    image_name = r.image_name,
+                    file_name = r.file_name
+                FROM jsonb_populate_record(NULL::tadgh_blog.blog_posts, $1) r
+                WHERE bp.id = $2
+                RETURNING bp.file_name, bp.slug
+                "#,
+                revision.snapshot,
+                revision.entity_id
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+            // Links built from the file name the post is moving away from keep working
+            if restored.file_name != current_file {
+                record_redirect(
+                    &mut tx,
+                    &file_permalink(&current_file),
+                    &permalink("blog_post", &restored.slug),
+                    "blog_post",
+                    revision.entity_id,
+                )
+                .await?;
+            }
+
+            let restoring_body = restore_body.unwrap_or(false) && revision.body.is_some();
+            let body = if restoring_body {
+                revision.body.clone()
+            } else if restored.file_name != current_file {
+                read_blog_markdown(&app, &restored.file_name).await
+            } else {
+                current_body
+            };
+            record_blog_post_revision(&mut tx, revision.entity_id, body.as_deref(), false).await?;
+            if body.is_some() {
+                index_blog_body(&mut tx, revision.entity_id, body.as_deref()).await?;
+            }
+
+            let (Some(body), true) = (body, restoring_body) else {
+                tx.commit().await.map_err(|e| e.to_string())?;
+                return Ok(());
+            };
+
+            // The file is written before the commit and put back if the commit fails
+            let path = load_current_profile(&app)
+                .await?
+                .blog_folder()
+                .ok_or("Blog folder path not configured")?
+                .join(&restored.file_name);
+            let _writing = watcher.expect_write(&restored.file_name);
+            let original = tokio::fs::read_to_string(&path).await.ok();
+            tokio::fs::write(&path, &body)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if let Err(e) = tx.commit().await {
+                let _ = match original {
+                    Some(original) => tokio::fs::write(&path, original).await,
+                    None => tokio::fs::remove_file(&path).await,
+                };
+                return Err(e.to_string());
+            }
+        }
+        "project" => {
+            record_project_revision(&mut tx, revision.entity_id, false).await?;
+
+            let result = sqlx::query!(
+                r#"
+                UPDATE tadgh_blog.projects p
+                SET title = r.title,
+                    description = r.description,
+                    image_name = r.image_name,
+                    url = r.url,
+                    created = r.created,
+                    released = r.released,
+                    live = r.live
+                FROM jsonb_populate_record(NULL::tadgh_blog.projects, $1) r
+                WHERE p.id = $2
+                "#,
+                revision.snapshot,
+                revision.entity_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+            if result.rows_affected() == 0 {
+                return Err("Project no longer exists".to_string());
+            }
+
+            record_project_revision(&mut tx, revision.entity_id, false).await?;
+
+            tx.commit().await.map_err(|e| e.to_string())?;
+        }
+        other => return Err(format!("Unknown entity type: {}", other)),
+    }
+
+    Ok(())
+}
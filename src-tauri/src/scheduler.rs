@@ -0,0 +1,395 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgPool;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{Mutex, Notify};
+
+use crate::settings::load_current_profile;
+use crate::AppState;
+
+// Upper bound on how long the scheduler sleeps before checking the database again
+const IDLE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ScheduledPublication {
+    pub id: i32,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub title: Option<String>,
+    pub publish_at: DateTime<Utc>,
+    pub run_deploy_hook: bool,
+    pub status: String,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeployHookResult {
+    pub success: bool,
+    pub output: String,
+}
+
+// Lets commands wake the background task when schedules change
+#[derive(Default)]
+pub struct Scheduler {
+    wake: Notify,
+}
+
+impl Scheduler {
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
+
+fn check_entity_type(entity_type: &str) -> Result<(), String> {
+    match entity_type {
+        "blog_post" | "project" => Ok(()),
+        _ => Err(format!("Unknown entity type: {}", entity_type)),
+    }
+}
+
+pub async fn current_pool(app: &AppHandle) -> Option<PgPool> {
+    let state = app.state::<Mutex<AppState>>();
+    let state = state.lock().await;
+    state.pool.clone()
+}
+
+// Background loop started from `setup`; pending schedules live in the database,
+// so anything missed while the app was closed is picked up on the next pass.
+pub async fn run(app: AppHandle) {
+    loop {
+        let next_due = match current_pool(&app).await {
+            Some(pool) => match publish_due(&app, &pool).await {
+                Ok(next_due) => next_due,
+                Err(e) => {
+                    let _ = app.emit("scheduled-publishing-error", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        // Schedules that are already due run right away rather than after the idle wait
+        let wait = next_due
+            .map(|at| (at - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+            .map_or(IDLE_INTERVAL, |until| until.min(IDLE_INTERVAL));
+
+        let scheduler = app.state::<Scheduler>();
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = scheduler.wake.notified() => {}
+        }
+    }
+}
+
+// Publishes everything that is due and returns when the next pending schedule fires
+async fn publish_due(app: &AppHandle, pool: &PgPool) -> Result<Option<DateTime<Utc>>, String> {
+    let due = sqlx::query!(
+        r#"
+        SELECT id, entity_type, entity_id, run_deploy_hook
+        FROM tadgh_blog.scheduled_publications
+        WHERE status = 'pending' AND publish_at <= NOW()
+        ORDER BY publish_at
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut run_deploy_hook = false;
+
+    for publication in due {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+        // Claimed first, so a cancellation that got in since the select above wins
+        let claimed = sqlx::query!(
+            r#"
+            UPDATE tadgh_blog.scheduled_publications
+            SET status = 'published', published_at = NOW(), last_error = NULL
+            WHERE id = $1 AND status = 'pending'
+            "#,
+            publication.id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
+        if claimed == 0 {
+            continue;
+        }
+
+        let updated = match publication.entity_type.as_str() {
+            "blog_post" => sqlx::query!(
+                "UPDATE tadgh_blog.blog_posts SET published = TRUE WHERE id = $1",
+                publication.entity_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected(),
+            _ => sqlx::query!(
+                "UPDATE tadgh_blog.projects SET released = TRUE WHERE id = $1",
+                publication.entity_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?
+            .rows_affected(),
+        };
+
+        if updated == 0 {
+            sqlx::query!(
+                r#"
+                UPDATE tadgh_blog.scheduled_publications
+                SET status = 'failed', published_at = NULL, last_error = 'Entity no longer exists'
+                WHERE id = $1
+                "#,
+                publication.id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        } else {
+            run_deploy_hook |= publication.run_deploy_hook;
+        }
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+
+        if let Ok(publication) = fetch_publication(pool, publication.id).await {
+            let _ = app.emit("scheduled-publication", publication);
+        }
+    }
+
+    if run_deploy_hook {
+        let result = run_deploy_hook_command(app).await;
+        let _ = app.emit("deploy-hook", result);
+    }
+
+    let next_due = sqlx::query_scalar!(
+        r#"
+        SELECT MIN(publish_at)
+        FROM tadgh_blog.scheduled_publications
+        WHERE status = 'pending'
+        "#
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(next_due)
+}
+
+async fn run_deploy_hook_command(app: &AppHandle) -> DeployHookResult {
+    let hook = match load_current_profile(app).await {
+        Ok(profile) => match profile.deploy_hook() {
+            Some(hook) => hook.to_string(),
+            None => {
+                return DeployHookResult {
+                    success: false,
+                    output: "No deploy hook configured for the current profile".to_string(),
+                }
+            }
+        },
+        Err(e) => {
+            return DeployHookResult {
+                success: false,
+                output: e,
+            }
+        }
+    };
+
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = tokio::process::Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = tokio::process::Command::new("sh");
+        command.arg("-c");
+        command
+    };
+
+    match command.arg(&hook).output().await {
+        Ok(output) => DeployHookResult {
+            success: output.status.success(),
+            output: format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        },
+        Err(e) => DeployHookResult {
+            success: false,
+            output: e.to_string(),
+        },
+    }
+}
+
+async fn fetch_publication(pool: &PgPool, id: i32) -> Result<ScheduledPublication, String> {
+    sqlx::query_as!(
+        ScheduledPublication,
+        r#"
+        SELECT
+            sp.id,
+            sp.entity_type,
+            sp.entity_id,
+            COALESCE(bp.title, p.title) as title,
+            sp.publish_at,
+            sp.run_deploy_hook,
+            sp.status,
+            sp.last_error,
+            sp.created_at,
+            sp.published_at
+        FROM tadgh_blog.scheduled_publications sp
+        LEFT JOIN tadgh_blog.blog_posts bp
+            ON sp.entity_type = 'blog_post' AND bp.id = sp.entity_id
+        LEFT JOIN tadgh_blog.projects p
+            ON sp.entity_type = 'project' AND p.id = sp.entity_id
+        WHERE sp.id = $1
+        "#,
+        id
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn schedule_publication(
+    state: State<'_, Mutex<AppState>>,
+    scheduler: State<'_, Scheduler>,
+    entity_type: String,
+    entity_id: i32,
+    publish_at: DateTime<Utc>,
+    run_deploy_hook: Option<bool>,
+) -> Result<ScheduledPublication, String> {
+    check_entity_type(&entity_type)?;
+    if publish_at <= Utc::now() {
+        return Err("Publish time must be in the future".to_string());
+    }
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    // Scheduling an entity that already has a pending publication moves it instead
+    let id = sqlx::query_scalar!(
+        r#"
+        INSERT INTO tadgh_blog.scheduled_publications
+        (entity_type, entity_id, publish_at, run_deploy_hook)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (entity_type, entity_id) WHERE status = 'pending'
+        DO UPDATE SET publish_at = EXCLUDED.publish_at,
+                      run_deploy_hook = EXCLUDED.run_deploy_hook
+        RETURNING id
+        "#,
+        entity_type,
+        entity_id,
+        publish_at,
+        run_deploy_hook.unwrap_or(false)
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    scheduler.wake();
+    fetch_publication(pool, id).await
+}
+
+#[tauri::command]
+pub async fn get_scheduled_publications(
+    state: State<'_, Mutex<AppState>>,
+    include_completed: Option<bool>,
+) -> Result<Vec<ScheduledPublication>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        ScheduledPublication,
+        r#"
+        SELECT
+            sp.id,
+            sp.entity_type,
+            sp.entity_id,
+            COALESCE(bp.title, p.title) as title,
+            sp.publish_at,
+            sp.run_deploy_hook,
+            sp.status,
+            sp.last_error,
+            sp.created_at,
+            sp.published_at
+        FROM tadgh_blog.scheduled_publications sp
+        LEFT JOIN tadgh_blog.blog_posts bp
+            ON sp.entity_type = 'blog_post' AND bp.id = sp.entity_id
+        LEFT JOIN tadgh_blog.projects p
+            ON sp.entity_type = 'project' AND p.id = sp.entity_id
+        WHERE sp.status = 'pending' OR $1
+        ORDER BY sp.publish_at ASC
+        "#,
+        include_completed.unwrap_or(false)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reschedule_publication(
+    state: State<'_, Mutex<AppState>>,
+    scheduler: State<'_, Scheduler>,
+    id: i32,
+    publish_at: DateTime<Utc>,
+) -> Result<ScheduledPublication, String> {
+    if publish_at <= Utc::now() {
+        return Err("Publish time must be in the future".to_string());
+    }
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.scheduled_publications
+        SET publish_at = $1
+        WHERE id = $2 AND status = 'pending'
+        "#,
+        publish_at,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err("No pending publication found".to_string());
+    }
+
+    scheduler.wake();
+    fetch_publication(pool, id).await
+}
+
+#[tauri::command]
+pub async fn cancel_publication(
+    state: State<'_, Mutex<AppState>>,
+    scheduler: State<'_, Scheduler>,
+    id: i32,
+) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.scheduled_publications
+        SET status = 'cancelled'
+        WHERE id = $1 AND status = 'pending'
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err("No pending publication found".to_string());
+    }
+
+    scheduler.wake();
+    Ok(())
+}
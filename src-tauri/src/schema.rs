@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use sqlx::migrate::Migrator;
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::scheduler::Scheduler;
+use crate::AppState;
+
+// The schema was managed by hand before the app shipped migrations, so they are only
+// applied when asked for, after they have been reviewed
+static MIGRATOR: Migrator = sqlx::migrate!();
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+    pub failed: bool, // Started but didn't finish, the database needs fixing by hand
+    pub sql: String,
+}
+
+// Versions recorded by sqlx, with whether they finished
+async fn applied_versions(pool: &PgPool) -> Result<HashMap<i64, bool>, String> {
+    let tracked: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !tracked {
+        return Ok(HashMap::new());
+    }
+
+    let rows: Vec<(i64, bool)> = sqlx::query_as("SELECT version, success FROM _sqlx_migrations")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().collect())
+}
+
+async fn migration_status(pool: &PgPool) -> Result<Vec<MigrationStatus>, String> {
+    let applied = applied_versions(pool).await?;
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied.get(&migration.version) == Some(&true),
+            failed: applied.get(&migration.version) == Some(&false),
+            sql: migration.sql.to_string(),
+        })
+        .collect())
+}
+
+// Every migration the app ships, with its SQL for review
+#[tauri::command]
+pub async fn get_schema_migrations(
+    state: State<'_, Mutex<AppState>>,
+) -> Result<Vec<MigrationStatus>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    migration_status(pool).await
+}
+
+// Applies the pending migrations in order, stopping at the first one that fails
+#[tauri::command]
+pub async fn apply_schema_migrations(
+    state: State<'_, Mutex<AppState>>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<Vec<MigrationStatus>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    MIGRATOR.run(pool).await.map_err(|e| e.to_string())?;
+
+    // Scheduling may have been failing on the old schema
+    scheduler.wake();
+    migration_status(pool).await
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::settings::load_current_profile;
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub title: String,
+    pub title_highlight: String,
+    pub snippet: String, // Matching fragments wrapped in <mark></mark>
+    pub rank: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchIndexStats {
+    pub documents: i64,
+    pub bodies_indexed: i64,
+}
+
+// Stores a post's markdown body in its search document; titles and descriptions
+// are kept up to date by the database trigger
+pub async fn index_blog_body(
+    conn: &mut PgConnection,
+    blog_post_id: i32,
+    body: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.search_documents
+        SET body = $2
+        WHERE entity_type = 'blog_post' AND entity_id = $1
+        "#,
+        blog_post_id,
+        body
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn search(
+    state: State<'_, Mutex<AppState>>,
+    query: String,
+    entity_types: Option<Vec<String>>,
+    tag_ids: Option<Vec<i32>>,
+    limit: Option<i64>,
+) -> Result<Vec<SearchResult>, String> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    // Tag filters apply to posts and projects through their tags, to tags directly
+    // and to categories through the tags they group
+    sqlx::query_as!(
+        SearchResult,
+        r#"
+        SELECT
+            sd.entity_type,
+            sd.entity_id,
+            sd.title,
+            ts_headline('english', sd.title, q,
+                'StartSel=<mark>, StopSel=</mark>, HighlightAll=true') as "title_highlight!",
+            ts_headline('english', concat_ws(' ', sd.content, sd.body), q,
+                'StartSel=<mark>, StopSel=</mark>, MaxFragments=2, MaxWords=30, MinWords=10') as "snippet!",
+            ts_rank(sd.document, q) as "rank!"
+        FROM tadgh_blog.search_documents sd,
+             websearch_to_tsquery('english', $1) q
+        WHERE sd.document @@ q
+          AND ($2::text[] IS NULL OR sd.entity_type = ANY($2))
+          AND (
+              $3::int[] IS NULL
+              OR (sd.entity_type = 'blog_post' AND EXISTS (
+                  SELECT 1 FROM tadgh_blog.blog_post_tags bpt
+                  WHERE bpt.blog_post_id = sd.entity_id AND bpt.tag_id = ANY($3)))
+              OR (sd.entity_type = 'project' AND EXISTS (
+                  SELECT 1 FROM tadgh_blog.project_tags pt
+                  WHERE pt.project_id = sd.entity_id AND pt.tag_id = ANY($3)))
+              OR (sd.entity_type = 'tag' AND sd.entity_id = ANY($3))
+              OR (sd.entity_type = 'category' AND EXISTS (
+                  SELECT 1 FROM tadgh_blog.tag_categories tc
+                  WHERE tc.category_id = sd.entity_id AND tc.tag_id = ANY($3)))
+          )
+        ORDER BY ts_rank(sd.document, q) DESC, sd.title ASC
+        LIMIT $4
+        "#,
+        query,
+        entity_types.as_deref(),
+        tag_ids.as_deref(),
+        limit.unwrap_or(50)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Re-reads every post's markdown from the blog folder into the index, or clears
+// the bodies when `include_bodies` is false
+#[tauri::command]
+pub async fn rebuild_search_index(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    include_bodies: Option<bool>,
+) -> Result<SearchIndexStats, String> {
+    let folder = if include_bodies.unwrap_or(true) {
+        Some(
+            load_current_profile(&app)
+                .await?
+                .blog_folder()
+                .ok_or("Blog folder path not configured")?,
+        )
+    } else {
+        None
+    };
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let posts = sqlx::query!("SELECT id, file_name FROM tadgh_blog.blog_posts")
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    for post in posts {
+        let body = match &folder {
+            Some(folder) => tokio::fs::read_to_string(folder.join(&post.file_name))
+                .await
+                .ok(),
+            None => None,
+        };
+        index_blog_body(&mut tx, post.id, body.as_deref()).await?;
+    }
+
+    let stats = sqlx::query_as!(
+        SearchIndexStats,
+        r#"
+        SELECT
+            COUNT(*) as "documents!",
+            COUNT(body) as "bodies_indexed!"
+        FROM tadgh_blog.search_documents
+        "#
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(stats)
+}
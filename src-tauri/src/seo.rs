@@ -0,0 +1,473 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::validation::{Validate, Validator};
+use crate::AppState;
+
+// Lengths search engines show in full without looking thin
+const TITLE_LENGTH: (usize, usize) = (30, 60);
+const DESCRIPTION_LENGTH: (usize, usize) = (70, 160);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Seo {
+    pub meta_title: Option<String>,       // Defaults to the title
+    pub meta_description: Option<String>, // Defaults to the description
+    pub canonical_url: Option<String>,    // Defaults to the permalink
+    pub og_image: Option<String>,         // File in the blog images folder, defaults to the hero
+    #[serde(default)]
+    pub noindex: bool,
+}
+
+impl Validate for Seo {
+    fn rules(&self, v: &mut Validator) {
+        v.max_length("meta_title", self.meta_title.as_deref(), 200)
+            .max_length("meta_description", self.meta_description.as_deref(), 1000)
+            .url("canonical_url", self.canonical_url.as_deref())
+            .file_name("og_image", self.og_image.as_deref());
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeoIssue {
+    pub severity: String, // "error", "warning" or "info"
+    // "title_too_long", "title_too_short", "duplicate_title", "description_missing",
+    // "description_too_long", "description_too_short", "duplicate_description",
+    // "missing_og_image" or "no_tags"
+    pub kind: String,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub title: String,
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SeoAuditOptions {
+    pub include_drafts: Option<bool>,
+    pub min_severity: Option<String>, // Leave out issues below this severity
+    pub sort_direction: Option<String>, // "desc" puts errors first (the default), "asc" info first
+}
+
+fn severity_rank(severity: &str) -> Result<u8, String> {
+    match severity {
+        "error" => Ok(3),
+        "warning" => Ok(2),
+        "info" => Ok(1),
+        other => Err(format!("Unknown severity: {}", other)),
+    }
+}
+
+// Trims the override and treats an empty one as unset
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn field_for(
+    value: &Option<String>,
+    override_field: &'static str,
+    field: &'static str,
+) -> &'static str {
+    if value.is_some() {
+        override_field
+    } else {
+        field
+    }
+}
+
+struct Audited {
+    entity_type: &'static str,
+    id: i32,
+    title: String,
+    seo_title: String,
+    seo_description: String,
+    // Where the fix goes: the override when one is set, otherwise the regular field
+    title_field: &'static str,
+    description_field: &'static str,
+    has_og_image: bool,
+    tag_count: Option<i64>, // Only checked for posts
+}
+
+impl Audited {
+    fn issue(&self, severity: &str, kind: &str, field: &str, message: String) -> SeoIssue {
+        SeoIssue {
+            severity: severity.to_string(),
+            kind: kind.to_string(),
+            entity_type: self.entity_type.to_string(),
+            entity_id: self.id,
+            title: self.title.clone(),
+            field: field.to_string(),
+            message,
+        }
+    }
+}
+
+fn check_lengths(item: &Audited) -> Vec<SeoIssue> {
+    let mut issues = Vec::new();
+
+    let title_length = item.seo_title.chars().count();
+    if title_length > TITLE_LENGTH.1 {
+        issues.push(item.issue(
+            "warning",
+            "title_too_long",
+            item.title_field,
+            format!(
+                "Title is {} characters, search results cut it off after about {}",
+                title_length, TITLE_LENGTH.1
+            ),
+        ));
+    } else if title_length < TITLE_LENGTH.0 {
+        issues.push(item.issue(
+            "info",
+            "title_too_short",
+            item.title_field,
+            format!(
+                "Title is {} characters, aim for at least {}",
+                title_length, TITLE_LENGTH.0
+            ),
+        ));
+    }
+
+    let description_length = item.seo_description.chars().count();
+    if description_length == 0 {
+        issues.push(item.issue(
+            "error",
+            "description_missing",
+            item.description_field,
+            "No description for search results or link previews".to_string(),
+        ));
+    } else if description_length > DESCRIPTION_LENGTH.1 {
+        issues.push(item.issue(
+            "warning",
+            "description_too_long",
+            item.description_field,
+            format!(
+                "Description is {} characters, search results cut it off after about {}",
+                description_length, DESCRIPTION_LENGTH.1
+            ),
+        ));
+    } else if description_length < DESCRIPTION_LENGTH.0 {
+        issues.push(item.issue(
+            "warning",
+            "description_too_short",
+            item.description_field,
+            format!(
+                "Description is {} characters, aim for at least {}",
+                description_length, DESCRIPTION_LENGTH.0
+            ),
+        ));
+    }
+
+    if !item.has_og_image {
+        issues.push(item.issue(
+            "warning",
+            "missing_og_image",
+            "og_image",
+            "No Open Graph image or hero image for link previews".to_string(),
+        ));
+    }
+
+    if item.tag_count == Some(0) {
+        issues.push(item.issue("info", "no_tags", "tags", "Post has no tags".to_string()));
+    }
+
+    issues
+}
+
+// Flags every post sharing a value with another post, case-insensitively
+fn check_duplicates(
+    posts: &[Audited],
+    kind: &str,
+    value: fn(&Audited) -> &str,
+    field: fn(&Audited) -> &'static str,
+    label: &str,
+) -> Vec<SeoIssue> {
+    let mut groups: HashMap<String, Vec<&Audited>> = HashMap::new();
+    for post in posts {
+        let key = value(post).trim().to_lowercase();
+        if !key.is_empty() {
+            groups.entry(key).or_default().push(post);
+        }
+    }
+
+    let mut issues = Vec::new();
+    for group in groups.values().filter(|group| group.len() > 1) {
+        for post in group {
+            let others: Vec<String> = group
+                .iter()
+                .filter(|other| other.id != post.id)
+                .map(|other| other.title.clone())
+                .collect();
+            issues.push(post.issue(
+                "error",
+                kind,
+                field(post),
+                format!("Same {} as {}", label, others.join(", ")),
+            ));
+        }
+    }
+    issues
+}
+
+async fn audit(
+    state: &State<'_, Mutex<AppState>>,
+    include_drafts: bool,
+) -> Result<Vec<SeoIssue>, String> {
+    let (posts, projects) = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+        let posts = sqlx::query!(
+            r#"
+            SELECT
+                bp.id, bp.title, bp.description, bp.image_name, bp.meta_title,
+                bp.meta_description, bp.og_image,
+                (SELECT COUNT(*) FROM tadgh_blog.blog_post_tags bpt
+                 WHERE bpt.blog_post_id = bp.id) as "tag_count!"
+            FROM tadgh_blog.blog_posts bp
+            WHERE NOT bp.noindex AND (bp.published OR $1)
+            ORDER BY bp.id
+            "#,
+            include_drafts
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let projects = sqlx::query!(
+            r#"
+            SELECT id, title, description, image_name, meta_title, meta_description, og_image
+            FROM tadgh_blog.projects
+            WHERE NOT noindex AND (released OR $1)
+            ORDER BY id
+            "#,
+            include_drafts
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        (posts, projects)
+    };
+
+    let posts: Vec<Audited> = posts
+        .into_iter()
+        .map(|row| {
+            let meta_title = clean(row.meta_title);
+            let meta_description = clean(row.meta_description);
+            Audited {
+                entity_type: "blog_post",
+                id: row.id,
+                title_field: field_for(&meta_title, "meta_title", "title"),
+                description_field: field_for(&meta_description, "meta_description", "description"),
+                seo_title: meta_title.unwrap_or_else(|| row.title.trim().to_string()),
+                seo_description: meta_description
+                    .unwrap_or_else(|| row.description.trim().to_string()),
+                has_og_image: clean(row.og_image).or(clean(row.image_name)).is_some(),
+                tag_count: Some(row.tag_count),
+                title: row.title,
+            }
+        })
+        .collect();
+
+    let projects: Vec<Audited> = projects
+        .into_iter()
+        .map(|row| {
+            let meta_title = clean(row.meta_title);
+            let meta_description = clean(row.meta_description);
+            Audited {
+                entity_type: "project",
+                id: row.id,
+                title_field: field_for(&meta_title, "meta_title", "title"),
+                description_field: field_for(&meta_description, "meta_description", "description"),
+                seo_title: meta_title.unwrap_or_else(|| row.title.trim().to_string()),
+                seo_description: meta_description
+                    .or(clean(row.description))
+                    .unwrap_or_default(),
+                has_og_image: clean(row.og_image).or(clean(row.image_name)).is_some(),
+                tag_count: None,
+                title: row.title,
+            }
+        })
+        .collect();
+
+    let mut issues = Vec::new();
+    for item in posts.iter().chain(projects.iter()) {
+        issues.extend(check_lengths(item));
+    }
+    issues.extend(check_duplicates(
+        &posts,
+        "duplicate_title",
+        |post| &post.seo_title,
+        |post| post.title_field,
+        "title",
+    ));
+    issues.extend(check_duplicates(
+        &posts,
+        "duplicate_description",
+        |post| &post.seo_description,
+        |post| post.description_field,
+        "description",
+    ));
+
+    Ok(issues)
+}
+
+#[tauri::command]
+pub async fn get_blog_post_seo(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+) -> Result<Seo, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        Seo,
+        r#"
+        SELECT meta_title, meta_description, canonical_url, og_image, noindex
+        FROM tadgh_blog.blog_posts
+        WHERE id = $1
+        "#,
+        blog_post_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Blog post not found".to_string())
+}
+
+#[tauri::command]
+pub async fn update_blog_post_seo(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+    seo: Seo,
+) -> Result<(), String> {
+    seo.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.blog_posts
+        SET meta_title = $1, meta_description = $2, canonical_url = $3, og_image = $4,
+            noindex = $5
+        WHERE id = $6
+        "#,
+        clean(seo.meta_title),
+        clean(seo.meta_description),
+        clean(seo.canonical_url),
+        clean(seo.og_image),
+        seo.noindex,
+        blog_post_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if updated.rows_affected() == 0 {
+        return Err("Blog post not found".to_string());
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_project_seo(
+    state: State<'_, Mutex<AppState>>,
+    project_id: i32,
+) -> Result<Seo, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        Seo,
+        r#"
+        SELECT meta_title, meta_description, canonical_url, og_image, noindex
+        FROM tadgh_blog.projects
+        WHERE id = $1
+        "#,
+        project_id
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Project not found".to_string())
+}
+
+#[tauri::command]
+pub async fn update_project_seo(
+    state: State<'_, Mutex<AppState>>,
+    project_id: i32,
+    seo: Seo,
+) -> Result<(), String> {
+    seo.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.projects
+        SET meta_title = $1, meta_description = $2, canonical_url = $3, og_image = $4,
+            noindex = $5
+        WHERE id = $6
+        "#,
+        clean(seo.meta_title),
+        clean(seo.meta_description),
+        clean(seo.canonical_url),
+        clean(seo.og_image),
+        seo.noindex,
+        project_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if updated.rows_affected() == 0 {
+        return Err("Project not found".to_string());
+    }
+
+    Ok(())
+}
+
+// Checks published posts and released projects (or everything with `include_drafts`)
+// for title and description lengths, duplicate post titles and descriptions, missing
+// Open Graph images and untagged posts. Anything marked noindex is skipped.
+#[tauri::command]
+pub async fn audit_seo(
+    state: State<'_, Mutex<AppState>>,
+    options: Option<SeoAuditOptions>,
+) -> Result<Vec<SeoIssue>, String> {
+    let options = options.unwrap_or_default();
+    let min_rank = match options.min_severity.as_deref() {
+        Some(severity) => severity_rank(severity)?,
+        None => 0,
+    };
+    let descending = match options.sort_direction.as_deref() {
+        Some("asc") => false,
+        Some("desc") | None => true,
+        Some(other) => return Err(format!("Unknown sort direction: {}", other)),
+    };
+
+    let mut issues: Vec<(u8, SeoIssue)> = audit(&state, options.include_drafts.unwrap_or(false))
+        .await?
+        .into_iter()
+        .map(|issue| Ok((severity_rank(&issue.severity)?, issue)))
+        .collect::<Result<Vec<_>, String>>()?;
+    issues.retain(|(rank, _)| *rank >= min_rank);
+
+    // Within a severity, issues stay grouped by post or project
+    issues.sort_by(|(a_rank, a), (b_rank, b)| {
+        let by_severity = if descending {
+            b_rank.cmp(a_rank)
+        } else {
+            a_rank.cmp(b_rank)
+        };
+        by_severity
+            .then_with(|| a.entity_type.cmp(&b.entity_type))
+            .then(a.entity_id.cmp(&b.entity_id))
+            .then_with(|| a.kind.cmp(&b.kind))
+    });
+
+    Ok(issues.into_iter().map(|(_, issue)| issue).collect())
+}
@@ -0,0 +1,362 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use std::collections::HashSet;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::slugs::{change_slug, permalink, resolve_slug};
+use crate::validation::validate_named;
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Series {
+    pub id: i32,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SeriesPost {
+    pub blog_post_id: i32,
+    pub position: i32,
+    pub title: String,
+    pub slug: String,
+    pub published: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeriesWithPosts {
+    pub series: Series,
+    pub posts: Vec<SeriesPost>,
+}
+
+// Where a post sits in its series, for "Part X of N" and previous/next links
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SeriesContext {
+    pub series: Series,
+    pub permalink: String,
+    pub position: i32,
+    pub total: i32,
+    pub previous: Option<SeriesPost>,
+    pub next: Option<SeriesPost>,
+}
+
+pub async fn get_series_posts_in(
+    conn: &mut PgConnection,
+    series_id: i32,
+) -> Result<Vec<SeriesPost>, String> {
+    sqlx::query_as!(
+        SeriesPost,
+        r#"
+        SELECT sp.blog_post_id, sp.position, bp.title, bp.slug, bp.published
+        FROM tadgh_blog.series_posts sp
+        JOIN tadgh_blog.blog_posts bp ON bp.id = sp.blog_post_id
+        WHERE sp.series_id = $1
+        ORDER BY sp.position ASC
+        "#,
+        series_id
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_series(state: State<'_, Mutex<AppState>>) -> Result<Vec<SeriesWithPosts>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let all_series = sqlx::query_as!(
+        Series,
+        r#"
+        SELECT id, name, slug, description, created_at
+        FROM tadgh_blog.series
+        ORDER BY name ASC
+        "#
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut result = Vec::with_capacity(all_series.len());
+    for series in all_series {
+        let posts = get_series_posts_in(&mut conn, series.id).await?;
+        result.push(SeriesWithPosts { series, posts });
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn create_series(
+    state: State<'_, Mutex<AppState>>,
+    name: String,
+    description: Option<String>,
+    slug: Option<String>,
+) -> Result<Series, String> {
+    validate_named(&name, description.as_deref(), slug.as_deref())?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let slug = resolve_slug(&mut tx, "series", slug.as_deref(), &name, None).await?;
+
+    let series = sqlx::query_as!(
+        Series,
+        r#"
+        INSERT INTO tadgh_blog.series (name, description, slug)
+        VALUES ($1, $2, $3)
+        RETURNING id, name, slug, description, created_at
+        "#,
+        name,
+        description,
+        slug
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(series)
+}
+
+#[tauri::command]
+pub async fn update_series(
+    state: State<'_, Mutex<AppState>>,
+    id: i32,
+    name: String,
+    description: Option<String>,
+    slug: Option<String>,
+) -> Result<(), String> {
+    validate_named(&name, description.as_deref(), slug.as_deref())?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let updated = sqlx::query!(
+        "UPDATE tadgh_blog.series SET name = $1, description = $2 WHERE id = $3",
+        name,
+        description,
+        id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if updated.rows_affected() == 0 {
+        return Err("Series not found".to_string());
+    }
+
+    if let Some(slug) = slug.as_deref().filter(|s| !s.trim().is_empty()) {
+        change_slug(&mut tx, "series", id, slug).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Posts in the series are kept, they just no longer belong to one
+#[tauri::command]
+pub async fn delete_series(state: State<'_, Mutex<AppState>>, id: i32) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    sqlx::query!("DELETE FROM tadgh_blog.series WHERE id = $1", id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Inserts the post at `position` (1-based, appended when omitted), moving it out of
+// any series it was already in
+#[tauri::command]
+pub async fn add_post_to_series(
+    state: State<'_, Mutex<AppState>>,
+    series_id: i32,
+    blog_post_id: i32,
+    position: Option<i32>,
+) -> Result<Vec<SeriesPost>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM tadgh_blog.series WHERE id = $1) as "exists!""#,
+        series_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if !exists {
+        return Err("Series not found".to_string());
+    }
+
+    // The series it leaves is renumbered by the series_posts_compact trigger
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.series_posts WHERE blog_post_id = $1",
+        blog_post_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*)::int as "count!" FROM tadgh_blog.series_posts WHERE series_id = $1"#,
+        series_id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let position = position.unwrap_or(count + 1).clamp(1, count + 1);
+
+    sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.series_posts
+        SET position = position + 1
+        WHERE series_id = $1 AND position >= $2
+        "#,
+        series_id,
+        position
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tadgh_blog.series_posts (series_id, blog_post_id, position)
+        VALUES ($1, $2, $3)
+        "#,
+        series_id,
+        blog_post_id,
+        position
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let posts = get_series_posts_in(&mut tx, series_id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(posts)
+}
+
+#[tauri::command]
+pub async fn remove_post_from_series(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+    // The rest of the series is renumbered by the series_posts_compact trigger
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.series_posts WHERE blog_post_id = $1",
+        blog_post_id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// `blog_post_ids` must list every post in the series exactly once, in the new order
+#[tauri::command]
+pub async fn reorder_series(
+    state: State<'_, Mutex<AppState>>,
+    series_id: i32,
+    blog_post_ids: Vec<i32>,
+) -> Result<Vec<SeriesPost>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let current: HashSet<i32> = sqlx::query_scalar!(
+        "SELECT blog_post_id FROM tadgh_blog.series_posts WHERE series_id = $1 FOR UPDATE",
+        series_id
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .collect();
+
+    let requested: HashSet<i32> = blog_post_ids.iter().copied().collect();
+    if requested.len() != blog_post_ids.len() || requested != current {
+        return Err("New order must contain each post in the series exactly once".to_string());
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE tadgh_blog.series_posts sp
+        SET position = o.position::int
+        FROM UNNEST($2::int[]) WITH ORDINALITY AS o(blog_post_id, position)
+        WHERE sp.series_id = $1 AND sp.blog_post_id = o.blog_post_id
+        "#,
+        series_id,
+        &blog_post_ids
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let posts = get_series_posts_in(&mut tx, series_id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(posts)
+}
+
+#[tauri::command]
+pub async fn get_blog_post_series(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+) -> Result<Option<SeriesContext>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+
+    let series = sqlx::query_as!(
+        Series,
+        r#"
+        SELECT s.id, s.name, s.slug, s.description, s.created_at
+        FROM tadgh_blog.series s
+        JOIN tadgh_blog.series_posts sp ON sp.series_id = s.id
+        WHERE sp.blog_post_id = $1
+        "#,
+        blog_post_id
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let Some(series) = series else {
+        return Ok(None);
+    };
+
+    let posts = get_series_posts_in(&mut conn, series.id).await?;
+    let index = posts
+        .iter()
+        .position(|post| post.blog_post_id == blog_post_id)
+        .ok_or("Blog post not found in series")?;
+
+    Ok(Some(SeriesContext {
+        permalink: permalink("series", &series.slug),
+        position: index as i32 + 1,
+        total: posts.len() as i32,
+        previous: index.checked_sub(1).map(|i| posts[i].clone()),
+        next: posts.get(index + 1).cloned(),
+        series,
+    }))
+}
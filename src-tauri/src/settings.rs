@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tauri::Manager;
 use tokio::*;
 
@@ -17,7 +18,68 @@ pub struct Profile {
     database_connection: DatabaseConnectionInfo,
     blog_images_path: Option<String>,
     blog_folder_path: Option<String>,
-    created_at: Option<String>, // ISO timestamp for sorting
+    created_at: Option<String>,  // ISO timestamp for sorting
+    deploy_hook: Option<String>, // Shell command run after scheduled publications
+    site_url: Option<String>,    // Public base URL, e.g. https://example.com
+    site_title: Option<String>,
+    site_output_path: Option<String>, // Where generated feeds and sitemaps are written
+    #[serde(default)]
+    custom_dictionary: Vec<String>, // Extra words the spell checker accepts, e.g. project names
+    #[serde(default)]
+    auto_create_posts: bool, // Draft posts are created for new markdown files in the blog folder
+}
+
+impl Profile {
+    pub fn blog_folder(&self) -> Option<PathBuf> {
+        non_empty_path(&self.blog_folder_path)
+    }
+
+    pub fn blog_images_folder(&self) -> Option<PathBuf> {
+        non_empty_path(&self.blog_images_path)
+    }
+
+    pub fn site_output_folder(&self) -> Option<PathBuf> {
+        non_empty_path(&self.site_output_path)
+    }
+
+    // Without a trailing slash so permalinks can be appended
+    pub fn site_url(&self) -> Option<&str> {
+        self.site_url
+            .as_deref()
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+    }
+
+    pub fn site_title(&self) -> Option<&str> {
+        self.site_title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty())
+    }
+
+    pub fn deploy_hook(&self) -> Option<&str> {
+        self.deploy_hook
+            .as_deref()
+            .filter(|hook| !hook.trim().is_empty())
+    }
+
+    pub fn custom_dictionary(&self) -> &[String] {
+        &self.custom_dictionary
+    }
+
+    pub fn set_custom_dictionary(&mut self, words: Vec<String>) {
+        self.custom_dictionary = words;
+    }
+
+    pub fn auto_create_posts(&self) -> bool {
+        self.auto_create_posts
+    }
+}
+
+fn non_empty_path(path: &Option<String>) -> Option<PathBuf> {
+    path.as_deref()
+        .filter(|path| !path.trim().is_empty())
+        .map(PathBuf::from)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -60,6 +122,12 @@ impl Settings {
                 blog_images_path: self.blog_images_path.clone(),
                 blog_folder_path: self.blog_folder_path.clone(),
                 created_at: Some(chrono::Utc::now().to_rfc3339()),
+                deploy_hook: None,
+                site_url: None,
+                site_title: None,
+                site_output_path: None,
+                custom_dictionary: Vec::new(),
+                auto_create_posts: false,
             };
 
             // Add to profiles if not already exists
@@ -259,6 +327,13 @@ pub async fn get_current_profile(app: tauri::AppHandle) -> Result<Option<Profile
     Ok(settings.get_current_profile())
 }
 
+// Used by backend tasks that need the active profile's paths or hooks
+pub async fn load_current_profile(app: &tauri::AppHandle) -> Result<Profile, String> {
+    get_current_profile(app.clone())
+        .await?
+        .ok_or_else(|| "No profile selected".to_string())
+}
+
 // Legacy command for backward compatibility
 #[tauri::command]
 pub async fn save_settings(settings: Settings, app: tauri::AppHandle) -> Result<(), String> {
@@ -266,15 +341,13 @@ pub async fn save_settings(settings: Settings, app: tauri::AppHandle) -> Result<
     if let Some(_) = &settings.current_profile {
         if let Some(current_profile) = get_current_profile(app.clone()).await? {
             let updated_profile = Profile {
-                name: current_profile.name,
-                database_connection: current_profile.database_connection,
                 blog_images_path: settings
                     .blog_images_path
                     .or(current_profile.blog_images_path),
                 blog_folder_path: settings
                     .blog_folder_path
                     .or(current_profile.blog_folder_path),
-                created_at: current_profile.created_at,
+                ..current_profile
             };
             save_profile(updated_profile, app).await?;
         }
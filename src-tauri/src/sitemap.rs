@@ -0,0 +1,309 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::feeds::escape_xml;
+use crate::settings::load_current_profile;
+use crate::slugs::permalink;
+use crate::validation::{Validate, Validator};
+use crate::AppState;
+
+// Protocol limits for a single sitemap file
+const MAX_URLS_PER_SITEMAP: usize = 50_000;
+const MAX_SITEMAP_BYTES: usize = 50 * 1024 * 1024;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SitemapOptions {
+    pub base_url: Option<String>, // Defaults to the profile's site URL
+    // Keyed by "home", "blog_post", "project", "tag" or "category"
+    pub priorities: Option<HashMap<String, f32>>,
+    pub max_urls_per_file: Option<usize>,
+    pub disallow: Option<Vec<String>>, // Extra robots.txt Disallow paths
+}
+
+impl Validate for SitemapOptions {
+    fn rules(&self, v: &mut Validator) {
+        v.url("base_url", self.base_url.as_deref());
+        // Each entry becomes its own robots.txt line, so no line breaks or other directives
+        for (index, path) in self.disallow.iter().flatten().enumerate() {
+            let field = format!("disallow.{}", index);
+            v.required(&field, path).site_path(&field, Some(path));
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SitemapResult {
+    pub files: Vec<String>, // Written files, relative to the output folder
+    pub urls: usize,
+}
+
+struct SitemapEntry {
+    location: String,
+    last_modified: Option<NaiveDate>,
+    change_frequency: &'static str,
+    priority: f32,
+}
+
+fn default_priority(entity_type: &str) -> f32 {
+    match entity_type {
+        "home" => 1.0,
+        "blog_post" => 0.8,
+        "project" => 0.6,
+        "category" => 0.5,
+        _ => 0.4,
+    }
+}
+
+fn render_url(entry: &SitemapEntry) -> String {
+    let mut xml = format!("<url><loc>{}</loc>", escape_xml(&entry.location));
+    if let Some(date) = entry.last_modified {
+        xml.push_str(&format!("<lastmod>{}</lastmod>", date.format("%Y-%m-%d")));
+    }
+    xml.push_str(&format!(
+        "<changefreq>{}</changefreq><priority>{:.1}</priority></url>\n",
+        entry.change_frequency, entry.priority
+    ));
+    xml
+}
+
+const URLSET_OPEN: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+    <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n";
+const URLSET_CLOSE: &str = "</urlset>\n";
+
+// Groups rendered <url> elements into documents that stay under both limits
+fn split_sitemaps(entries: &[SitemapEntry], max_urls: usize) -> Vec<(String, Option<NaiveDate>)> {
+    let mut documents = Vec::new();
+    let mut current = String::from(URLSET_OPEN);
+    let mut count = 0;
+    let mut latest: Option<NaiveDate> = None;
+
+    for entry in entries {
+        let url = render_url(entry);
+        let full =
+            count >= max_urls || current.len() + url.len() + URLSET_CLOSE.len() > MAX_SITEMAP_BYTES;
+        if full && count > 0 {
+            current.push_str(URLSET_CLOSE);
+            documents.push((current, latest));
+            current = String::from(URLSET_OPEN);
+            count = 0;
+            latest = None;
+        }
+        current.push_str(&url);
+        count += 1;
+        latest = latest.max(entry.last_modified);
+    }
+
+    current.push_str(URLSET_CLOSE);
+    documents.push((current, latest));
+    documents
+}
+
+// Shards from an earlier run with more URLs would otherwise still be published
+async fn remove_stale_shards(output_folder: &Path, files: &[String]) -> Result<(), String> {
+    let mut entries = tokio::fs::read_dir(output_folder)
+        .await
+        .map_err(|e| e.to_string())?;
+    while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let is_shard = name
+            .strip_prefix("sitemap-")
+            .and_then(|rest| rest.strip_suffix(".xml"))
+            .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+        if is_shard && !files.contains(&name) {
+            tokio::fs::remove_file(entry.path())
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+// Writes sitemap.xml (an index pointing at sitemap-1.xml, sitemap-2.xml, ... when the
+// URLs don't fit in one file) and robots.txt to the site output folder. Covers the
+// home page, published posts, released projects and tags and categories with content.
+#[tauri::command]
+pub async fn generate_sitemap(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    options: Option<SitemapOptions>,
+) -> Result<SitemapResult, String> {
+    let options = options.unwrap_or_default();
+    options.validate()?;
+    let profile = load_current_profile(&app).await?;
+    let base_url = options
+        .base_url
+        .as_deref()
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty())
+        .or(profile.site_url())
+        .ok_or("Site URL not configured")?
+        .to_string();
+    let output_folder = profile
+        .site_output_folder()
+        .ok_or("Site output path not configured")?;
+    let max_urls = options
+        .max_urls_per_file
+        .unwrap_or(MAX_URLS_PER_SITEMAP)
+        .clamp(1, MAX_URLS_PER_SITEMAP);
+
+    let priority = |entity_type: &str| {
+        options
+            .priorities
+            .as_ref()
+            .and_then(|priorities| priorities.get(entity_type))
+            .copied()
+            .unwrap_or_else(|| default_priority(entity_type))
+            .clamp(0.0, 1.0)
+    };
+
+    let rows = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+
+        // Last modified is the newest of the created date and the latest revision;
+        // tags and categories take the newest date of their listed content. Pages marked
+        // noindex or canonical to another site are left out.
+        sqlx::query!(
+            r#"
+            WITH posts AS (
+                SELECT bp.id, bp.slug, GREATEST(bp.created, (
+                    SELECT MAX(r.created_at)::date FROM tadgh_blog.revisions r
+                    WHERE r.entity_type = 'blog_post' AND r.entity_id = bp.id
+                )) as last_modified
+                FROM tadgh_blog.blog_posts bp
+                WHERE bp.published AND NOT bp.noindex
+                  AND (COALESCE(TRIM(bp.canonical_url), '') IN ('', $1)
+                       OR (bp.canonical_url LIKE '/%' AND bp.canonical_url NOT LIKE '//%')
+                       OR starts_with(bp.canonical_url, $1 || '/'))
+            ),
+            projects AS (
+                SELECT p.id, p.slug, p.live, GREATEST(p.created, (
+                    SELECT MAX(r.created_at)::date FROM tadgh_blog.revisions r
+                    WHERE r.entity_type = 'project' AND r.entity_id = p.id
+                )) as last_modified
+                FROM tadgh_blog.projects p
+                WHERE p.released AND NOT p.noindex
+                  AND (COALESCE(TRIM(p.canonical_url), '') IN ('', $1)
+                       OR (p.canonical_url LIKE '/%' AND p.canonical_url NOT LIKE '//%')
+                       OR starts_with(p.canonical_url, $1 || '/'))
+            ),
+            tagged AS (
+                SELECT bpt.tag_id, po.last_modified
+                FROM posts po JOIN tadgh_blog.blog_post_tags bpt ON bpt.blog_post_id = po.id
+                UNION ALL
+                SELECT pt.tag_id, pr.last_modified
+                FROM projects pr JOIN tadgh_blog.project_tags pt ON pt.project_id = pr.id
+            )
+            SELECT 'blog_post' as "entity_type!", slug as "slug!", last_modified, FALSE as "live!"
+            FROM posts
+            UNION ALL
+            SELECT 'project', slug, last_modified, live
+            FROM projects
+            UNION ALL
+            SELECT 'tag', t.slug, MAX(tg.last_modified), FALSE
+            FROM tadgh_blog.tags t JOIN tagged tg ON tg.tag_id = t.id
+            GROUP BY t.id
+            UNION ALL
+            SELECT 'category', c.slug, MAX(tg.last_modified), FALSE
+            FROM tadgh_blog.categories c
+            JOIN tadgh_blog.tag_categories tc ON tc.category_id = c.id
+            JOIN tagged tg ON tg.tag_id = tc.tag_id
+            GROUP BY c.id
+            "#,
+            base_url
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+    };
+
+    let mut entries = vec![SitemapEntry {
+        location: format!("{}/", base_url),
+        last_modified: rows
+            .iter()
+            .filter(|row| row.entity_type == "blog_post")
+            .filter_map(|row| row.last_modified)
+            .max(),
+        change_frequency: "daily",
+        priority: priority("home"),
+    }];
+
+    for row in &rows {
+        entries.push(SitemapEntry {
+            location: format!("{}{}", base_url, permalink(&row.entity_type, &row.slug)),
+            last_modified: row.last_modified,
+            change_frequency: match row.entity_type.as_str() {
+                "blog_post" => "monthly",
+                "project" if row.live => "monthly",
+                "project" => "yearly",
+                _ => "weekly",
+            },
+            priority: priority(&row.entity_type),
+        });
+    }
+
+    tokio::fs::create_dir_all(&output_folder)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let documents = split_sitemaps(&entries, max_urls);
+    let mut files = Vec::new();
+
+    if documents.len() == 1 {
+        let (document, _) = &documents[0];
+        tokio::fs::write(output_folder.join("sitemap.xml"), document)
+            .await
+            .map_err(|e| e.to_string())?;
+        files.push("sitemap.xml".to_string());
+    } else {
+        let mut index = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+        for (i, (document, latest)) in documents.iter().enumerate() {
+            let file_name = format!("sitemap-{}.xml", i + 1);
+            tokio::fs::write(output_folder.join(&file_name), document)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            index.push_str(&format!(
+                "<sitemap><loc>{}/{}</loc>",
+                escape_xml(&base_url),
+                file_name
+            ));
+            if let Some(date) = latest {
+                index.push_str(&format!("<lastmod>{}</lastmod>", date.format("%Y-%m-%d")));
+            }
+            index.push_str("</sitemap>\n");
+            files.push(file_name);
+        }
+        index.push_str("</sitemapindex>\n");
+
+        tokio::fs::write(output_folder.join("sitemap.xml"), index)
+            .await
+            .map_err(|e| e.to_string())?;
+        files.insert(0, "sitemap.xml".to_string());
+    }
+    remove_stale_shards(&output_folder, &files).await?;
+
+    let mut robots = String::from("User-agent: *\nAllow: /\n");
+    for path in options.disallow.iter().flatten() {
+        robots.push_str(&format!("Disallow: {}\n", path));
+    }
+    robots.push_str(&format!("\nSitemap: {}/sitemap.xml\n", base_url));
+    tokio::fs::write(output_folder.join("robots.txt"), robots)
+        .await
+        .map_err(|e| e.to_string())?;
+    files.push("robots.txt".to_string());
+
+    Ok(SitemapResult {
+        files,
+        urls: entries.len(),
+    })
+}
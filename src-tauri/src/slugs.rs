@@ -0,0 +1,448 @@
+use serde::{Deserialize, Serialize};
+use sqlx::PgConnection;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::validation::{Validate, Validator};
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Redirect {
+    pub id: i32,
+    pub from_path: String,
+    pub to_path: String,
+    pub status_code: i32,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Lowercase ASCII words joined by single dashes, matching tadgh_blog.slugify() in the migrations
+pub fn slugify(value: &str) -> String {
+    let mut slug = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+// Inputs of `create_redirect` and `update_slug`, which take them as separate arguments
+struct NewRedirect<'a> {
+    from_path: &'a str,
+    to_path: &'a str,
+    status_code: Option<i32>,
+}
+
+impl Validate for NewRedirect<'_> {
+    fn rules(&self, v: &mut Validator) {
+        v.required("from_path", self.from_path)
+            .site_path("from_path", Some(self.from_path))
+            .max_length("from_path", Some(self.from_path), 2000)
+            .required("to_path", self.to_path)
+            .max_length("to_path", Some(self.to_path), 2000)
+            .one_of("status_code", self.status_code, &[301, 302, 307, 308]);
+        // Redirects may also point off the site
+        if self.to_path.starts_with("http://") || self.to_path.starts_with("https://") {
+            v.url("to_path", Some(self.to_path));
+        } else {
+            v.site_path("to_path", Some(self.to_path));
+        }
+    }
+}
+
+struct SlugUpdate<'a> {
+    slug: &'a str,
+}
+
+impl Validate for SlugUpdate<'_> {
+    fn rules(&self, v: &mut Validator) {
+        v.required("slug", self.slug)
+            .max_length("slug", Some(self.slug), 200)
+            .slug("slug", Some(self.slug));
+    }
+}
+
+fn table_for(entity_type: &str) -> Result<&'static str, String> {
+    match entity_type {
+        "blog_post" => Ok("blog_posts"),
+        "project" => Ok("projects"),
+        "tag" => Ok("tags"),
+        "category" => Ok("categories"),
+        "series" => Ok("series"),
+        "author" => Ok("authors"),
+        "translation" => Ok("blog_post_translations"),
+        _ => Err(format!("Unknown entity type: {}", entity_type)),
+    }
+}
+
+// Public URL an entity is served under on the site
+pub fn permalink(entity_type: &str, slug: &str) -> String {
+    let prefix = match entity_type {
+        "blog_post" => "/blog",
+        "project" => "/projects",
+        "tag" => "/tags",
+        "series" => "/series",
+        "author" => "/authors",
+        _ => "/categories",
+    };
+    format!("{}/{}", prefix, slug)
+}
+
+// Translations are served under their locale, e.g. /fr/blog/mon-article
+pub fn localized_permalink(locale: &str, slug: &str) -> String {
+    format!("/{}{}", locale, permalink("blog_post", slug))
+}
+
+// Files in the images folder are published under /images
+pub fn image_permalink(file_name: &str) -> String {
+    format!("/images/{}", file_name)
+}
+
+// Old posts were served by file name, e.g. `/blog/my-post` for `my-post.md`
+pub fn file_permalink(file_name: &str) -> String {
+    let stem = std::path::Path::new(file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
+    permalink("blog_post", &slugify(stem))
+}
+
+// Slugifies `requested` (or `fallback` when none was given) and appends -2, -3, ...
+// until it no longer clashes with another row of the same entity type
+pub async fn resolve_slug(
+    conn: &mut PgConnection,
+    entity_type: &str,
+    requested: Option<&str>,
+    fallback: &str,
+    exclude_id: Option<i32>,
+) -> Result<String, String> {
+    let table = table_for(entity_type)?;
+    let mut base = slugify(
+        requested
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or(fallback),
+    );
+    if base.is_empty() {
+        base = entity_type.replace('_', "-");
+    }
+
+    let query = format!(
+        "SELECT EXISTS (SELECT 1 FROM tadgh_blog.{} WHERE slug = $1 AND id <> $2)",
+        table
+    );
+
+    let mut candidate = base.clone();
+    let mut suffix = 2;
+    loop {
+        let taken: bool = sqlx::query_scalar(&query)
+            .bind(&candidate)
+            .bind(exclude_id.unwrap_or(0))
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !taken {
+            return Ok(candidate);
+        }
+
+        candidate = format!("{}-{}", base, suffix);
+        suffix += 1;
+    }
+}
+
+// Whether some entity is currently served at `path`, mirroring `permalink` and
+// `localized_permalink`
+pub async fn permalink_in_use(conn: &mut PgConnection, path: &str) -> Result<bool, String> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM tadgh_blog.blog_posts WHERE '/blog/' || slug = $1
+            UNION ALL
+            SELECT 1 FROM tadgh_blog.blog_post_translations
+            WHERE '/' || locale || '/blog/' || slug = $1
+            UNION ALL
+            SELECT 1 FROM tadgh_blog.projects WHERE '/projects/' || slug = $1
+            UNION ALL
+            SELECT 1 FROM tadgh_blog.tags WHERE '/tags/' || slug = $1
+            UNION ALL
+            SELECT 1 FROM tadgh_blog.categories WHERE '/categories/' || slug = $1
+            UNION ALL
+            SELECT 1 FROM tadgh_blog.series WHERE '/series/' || slug = $1
+            UNION ALL
+            SELECT 1 FROM tadgh_blog.authors WHERE '/authors/' || slug = $1
+        ) AS "in_use!"
+        "#,
+        path
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Records a permanent redirect, repointing older redirects at the new target so
+// chains never form, and dropping any redirect away from a path that is live again.
+// Nothing is recorded when `from_path` still belongs to another entity, since the
+// redirect would hide it
+pub async fn record_redirect(
+    conn: &mut PgConnection,
+    from_path: &str,
+    to_path: &str,
+    entity_type: &str,
+    entity_id: i32,
+) -> Result<(), String> {
+    if from_path == to_path || permalink_in_use(conn, from_path).await? {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.redirects WHERE from_path = $1",
+        to_path
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.redirects SET to_path = $1 WHERE to_path = $2",
+        to_path,
+        from_path
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tadgh_blog.redirects (from_path, to_path, entity_type, entity_id)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (from_path) DO UPDATE
+        SET to_path = EXCLUDED.to_path,
+            entity_type = EXCLUDED.entity_type,
+            entity_id = EXCLUDED.entity_id
+        "#,
+        from_path,
+        to_path,
+        entity_type,
+        entity_id
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn render_redirects(redirects: &[Redirect], format: &str) -> Result<String, String> {
+    match format {
+        "nginx" => Ok(redirects
+            .iter()
+            .map(|r| {
+                format!(
+                    "location = {} {{ return {} {}; }}\n",
+                    r.from_path, r.status_code, r.to_path
+                )
+            })
+            .collect()),
+        "netlify" => Ok(redirects
+            .iter()
+            .map(|r| format!("{}  {}  {}\n", r.from_path, r.to_path, r.status_code))
+            .collect()),
+        "json" => {
+            let rules: Vec<serde_json::Value> = redirects
+                .iter()
+                .map(|r| {
+                    serde_json::json!({
+                        "from": r.from_path,
+                        "to": r.to_path,
+                        "status": r.status_code,
+                    })
+                })
+                .collect();
+            serde_json::to_string_pretty(&rules).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("Unknown redirect format: {}", format)),
+    }
+}
+
+#[tauri::command]
+pub async fn generate_slug(
+    state: State<'_, Mutex<AppState>>,
+    entity_type: String,
+    value: String,
+    exclude_id: Option<i32>,
+) -> Result<String, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    resolve_slug(&mut conn, &entity_type, None, &value, exclude_id).await
+}
+
+// Moves an entity to a new slug, recording a redirect from its old permalink
+pub async fn change_slug(
+    conn: &mut PgConnection,
+    entity_type: &str,
+    entity_id: i32,
+    requested: &str,
+) -> Result<String, String> {
+    let table = table_for(entity_type)?;
+
+    let old_slug: String = sqlx::query_scalar(&format!(
+        "SELECT slug FROM tadgh_blog.{} WHERE id = $1 FOR UPDATE",
+        table
+    ))
+    .bind(entity_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Entity not found")?;
+
+    if requested == old_slug {
+        return Ok(old_slug);
+    }
+
+    let new_slug = resolve_slug(
+        conn,
+        entity_type,
+        Some(requested),
+        &old_slug,
+        Some(entity_id),
+    )
+    .await?;
+
+    if new_slug != old_slug {
+        sqlx::query(&format!(
+            "UPDATE tadgh_blog.{} SET slug = $1 WHERE id = $2",
+            table
+        ))
+        .bind(&new_slug)
+        .bind(entity_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        record_redirect(
+            conn,
+            &permalink(entity_type, &old_slug),
+            &permalink(entity_type, &new_slug),
+            entity_type,
+            entity_id,
+        )
+        .await?;
+    }
+
+    Ok(new_slug)
+}
+
+#[tauri::command]
+pub async fn update_slug(
+    state: State<'_, Mutex<AppState>>,
+    entity_type: String,
+    entity_id: i32,
+    slug: String,
+) -> Result<String, String> {
+    SlugUpdate { slug: &slug }.validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let slug = change_slug(&mut tx, &entity_type, entity_id, &slug).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(slug)
+}
+
+#[tauri::command]
+pub async fn get_redirects(state: State<'_, Mutex<AppState>>) -> Result<Vec<Redirect>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        Redirect,
+        r#"
+        SELECT id, from_path, to_path, status_code, entity_type, entity_id, created_at
+        FROM tadgh_blog.redirects
+        ORDER BY from_path ASC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_redirect(
+    state: State<'_, Mutex<AppState>>,
+    from_path: String,
+    to_path: String,
+    status_code: Option<i32>,
+) -> Result<Redirect, String> {
+    NewRedirect {
+        from_path: &from_path,
+        to_path: &to_path,
+        status_code,
+    }
+    .validate()?;
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+    if permalink_in_use(&mut conn, &from_path).await? {
+        return Err(format!("{} is the permalink of a live page", from_path));
+    }
+
+    sqlx::query_as!(
+        Redirect,
+        r#"
+        INSERT INTO tadgh_blog.redirects (from_path, to_path, status_code)
+        VALUES ($1, $2, $3)
+        RETURNING id, from_path, to_path, status_code, entity_type, entity_id, created_at
+        "#,
+        from_path,
+        to_path,
+        status_code.unwrap_or(301)
+    )
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_redirect(state: State<'_, Mutex<AppState>>, id: i32) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query!(
+        r#"
+        DELETE FROM tadgh_blog.redirects
+        WHERE id = $1
+        "#,
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// Renders every redirect as nginx `location` blocks, a Netlify `_redirects` file or JSON,
+// optionally writing the result to `output_path`
+#[tauri::command]
+pub async fn export_redirects(
+    state: State<'_, Mutex<AppState>>,
+    format: String,
+    output_path: Option<String>,
+) -> Result<String, String> {
+    let redirects = get_redirects(state).await?;
+    let rendered = render_redirects(&redirects, &format)?;
+
+    if let Some(path) = output_path {
+        tokio::fs::write(&path, &rendered)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(rendered)
+}
@@ -0,0 +1,859 @@
+use comrak::nodes::NodeValue;
+use comrak::{parse_document, Arena};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::lint::{find_urls, Lines};
+use crate::preview::markdown_options;
+use crate::settings::{load_current_profile, save_profile};
+use crate::AppState;
+
+const DEFAULT_LANGUAGE: &str = "en_US";
+const MAX_SUGGESTIONS: usize = 5;
+
+const BE_VERBS: &[&str] = &["am", "is", "are", "was", "were", "be", "been", "being"];
+
+// Past participles that don't end in -ed
+const IRREGULAR_PARTICIPLES: &[&str] = &[
+    "begun",
+    "broken",
+    "brought",
+    "built",
+    "bought",
+    "caught",
+    "chosen",
+    "done",
+    "drawn",
+    "driven",
+    "eaten",
+    "fallen",
+    "found",
+    "forgotten",
+    "given",
+    "gone",
+    "grown",
+    "heard",
+    "held",
+    "hidden",
+    "kept",
+    "known",
+    "laid",
+    "led",
+    "left",
+    "lost",
+    "made",
+    "meant",
+    "met",
+    "paid",
+    "put",
+    "read",
+    "run",
+    "said",
+    "seen",
+    "sent",
+    "set",
+    "shown",
+    "shut",
+    "sold",
+    "spent",
+    "spoken",
+    "stolen",
+    "taken",
+    "taught",
+    "thought",
+    "thrown",
+    "told",
+    "understood",
+    "won",
+    "worn",
+    "written",
+];
+
+// Phrases with a shorter way of saying the same thing
+const WORDY_PHRASES: &[(&str, &str)] = &[
+    ("in order to", "to"),
+    ("due to the fact that", "because"),
+    ("in spite of the fact that", "although"),
+    ("at this point in time", "now"),
+    ("in the event that", "if"),
+    ("a large number of", "many"),
+    ("for the purpose of", "for"),
+    ("with regard to", "about"),
+    ("has the ability to", "can"),
+    ("it is important to note that", "note that"),
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SpellCheckOptions {
+    pub language: Option<String>, // Dictionary name, "en_US" by default
+    pub spelling: Option<bool>,   // On by default
+    pub style: Option<bool>,      // Repeated words, passive voice and wordy phrases, on by default
+}
+
+// Lines and columns are 1-based, columns count characters and the end is inclusive
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SpellingDiagnostic {
+    pub kind: String, // "spelling", "repeated_word", "passive_voice", "wordy_phrase" or "dictionary_missing"
+    pub severity: String, // "error" for spelling, "warning" or "info" for style
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub text: String,
+    pub message: String,
+    pub suggestions: Vec<String>,
+}
+
+// One affix rule, e.g. `SFX S y ies [^aeiou]y`
+struct AffixRule {
+    strip: String,
+    add: String,
+    condition: Vec<(bool, Vec<char>)>, // Per character: (negated, allowed); empty allows any
+}
+
+impl AffixRule {
+    fn matches(&self, chars: &[char]) -> bool {
+        chars.len() >= self.condition.len()
+            && self
+                .condition
+                .iter()
+                .zip(chars)
+                .all(|((negated, allowed), c)| {
+                    allowed.is_empty() || allowed.contains(c) != *negated
+                })
+    }
+}
+
+struct AffixClass {
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+// Word forms generated from a Hunspell .aff/.dic pair. Only plain prefix and suffix
+// rules are expanded; compounding and morphology are ignored.
+pub struct Dictionary {
+    words: HashSet<String>,
+    try_chars: Vec<char>,
+}
+
+fn parse_condition(condition: &str) -> Vec<(bool, Vec<char>)> {
+    let mut parsed = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => parsed.push((false, Vec::new())),
+            '[' => {
+                let mut set: Vec<char> = chars.by_ref().take_while(|c| *c != ']').collect();
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                parsed.push((negated, set));
+            }
+            c => parsed.push((false, vec![c])),
+        }
+    }
+    parsed
+}
+
+fn parse_flags(flags: &str, flag_type: &str) -> Vec<String> {
+    match flag_type {
+        "long" => flags
+            .chars()
+            .collect::<Vec<char>>()
+            .chunks(2)
+            .map(|pair| pair.iter().collect())
+            .collect(),
+        "num" => flags
+            .split(',')
+            .map(|flag| flag.trim().to_string())
+            .collect(),
+        _ => flags.chars().map(String::from).collect(),
+    }
+}
+
+// Hunspell files are UTF-8 or ISO8859-1, as declared by SET in the .aff file
+fn decode(bytes: &[u8], encoding: &str) -> String {
+    if encoding.eq_ignore_ascii_case("UTF-8") {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|b| *b as char).collect()
+    }
+}
+
+impl Dictionary {
+    pub fn parse(aff_bytes: &[u8], dic_bytes: &[u8]) -> Dictionary {
+        let encoding = String::from_utf8_lossy(aff_bytes)
+            .lines()
+            .find_map(|line| line.strip_prefix("SET ").map(|set| set.trim().to_string()))
+            .unwrap_or_else(|| "UTF-8".to_string());
+        let aff = decode(aff_bytes, &encoding);
+        let dic = decode(dic_bytes, &encoding);
+
+        let mut flag_type = "char".to_string();
+        let mut try_chars = Vec::new();
+        let mut skip_flags = HashSet::new(); // NEEDAFFIX and FORBIDDENWORD stems aren't words
+        let mut prefixes: HashMap<String, AffixClass> = HashMap::new();
+        let mut suffixes: HashMap<String, AffixClass> = HashMap::new();
+
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["FLAG", kind, ..] => flag_type = kind.to_string(),
+                ["TRY", chars, ..] => try_chars = chars.chars().collect(),
+                ["NEEDAFFIX" | "FORBIDDENWORD", flag, ..] => {
+                    skip_flags.insert(flag.to_string());
+                }
+                [kind @ ("PFX" | "SFX"), flag, cross, count] if count.parse::<usize>().is_ok() => {
+                    let classes = if *kind == "PFX" {
+                        &mut prefixes
+                    } else {
+                        &mut suffixes
+                    };
+                    classes.insert(
+                        flag.to_string(),
+                        AffixClass {
+                            cross_product: *cross == "Y",
+                            rules: Vec::new(),
+                        },
+                    );
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, rest @ ..] => {
+                    let classes = if *kind == "PFX" {
+                        &mut prefixes
+                    } else {
+                        &mut suffixes
+                    };
+                    let Some(class) = classes.get_mut(*flag) else {
+                        continue;
+                    };
+                    let add = add.split('/').next().unwrap_or_default();
+                    let mut condition = parse_condition(rest.first().copied().unwrap_or("."));
+                    // Suffix conditions are written left to right but match the word's end
+                    if *kind == "SFX" {
+                        condition.reverse();
+                    }
+                    class.rules.push(AffixRule {
+                        strip: if *strip == "0" { "" } else { strip }.to_string(),
+                        add: if add == "0" { "" } else { add }.to_string(),
+                        condition,
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        let mut words = HashSet::new();
+        for entry in dic.lines().skip(1) {
+            let entry = entry.split(['\t', ' ']).next().unwrap_or_default();
+            let (stem, flags) = match entry.split_once('/') {
+                Some((stem, flags)) => (stem, parse_flags(flags, &flag_type)),
+                None => (entry, Vec::new()),
+            };
+            if stem.is_empty() {
+                continue;
+            }
+            if !flags.iter().any(|flag| skip_flags.contains(flag)) {
+                words.insert(stem.to_string());
+            }
+
+            let stem_chars: Vec<char> = stem.chars().collect();
+            let reversed: Vec<char> = stem_chars.iter().rev().copied().collect();
+            let mut cross_forms = vec![stem.to_string()];
+
+            for flag in &flags {
+                let Some(class) = suffixes.get(flag) else {
+                    continue;
+                };
+                for rule in &class.rules {
+                    if rule.matches(&reversed) {
+                        if let Some(base) = stem.strip_suffix(rule.strip.as_str()) {
+                            let form = format!("{}{}", base, rule.add);
+                            if class.cross_product {
+                                cross_forms.push(form.clone());
+                            }
+                            words.insert(form);
+                        }
+                    }
+                }
+            }
+
+            for flag in &flags {
+                let Some(class) = prefixes.get(flag) else {
+                    continue;
+                };
+                for rule in class.rules.iter().filter(|rule| rule.matches(&stem_chars)) {
+                    let forms = if class.cross_product {
+                        &cross_forms[..]
+                    } else {
+                        &cross_forms[..1]
+                    };
+                    for form in forms {
+                        if let Some(rest) = form.strip_prefix(rule.strip.as_str()) {
+                            words.insert(format!("{}{}", rule.add, rest));
+                        }
+                    }
+                }
+            }
+        }
+
+        Dictionary { words, try_chars }
+    }
+
+    // Title case words also match their lowercase entry, e.g. at the start of a sentence
+    fn contains(&self, word: &str) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+        let mut chars = word.chars();
+        let first_upper = chars.next().is_some_and(char::is_uppercase);
+        first_upper && self.words.contains(&word.to_lowercase())
+    }
+
+    // Known words one edit away, keeping the original's capitalisation
+    fn suggest(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.to_lowercase().chars().collect();
+        let alphabet: Vec<char> = if self.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.try_chars
+                .iter()
+                .filter(|c| c.is_lowercase())
+                .copied()
+                .collect()
+        };
+
+        let mut candidates: Vec<Vec<char>> = Vec::new();
+        for i in 0..chars.len() {
+            let mut deleted = chars.clone();
+            deleted.remove(i);
+            candidates.push(deleted);
+            if i + 1 < chars.len() {
+                let mut swapped = chars.clone();
+                swapped.swap(i, i + 1);
+                candidates.push(swapped);
+            }
+            for c in &alphabet {
+                let mut replaced = chars.clone();
+                replaced[i] = *c;
+                candidates.push(replaced);
+            }
+        }
+        for i in 0..=chars.len() {
+            for c in &alphabet {
+                let mut inserted = chars.clone();
+                inserted.insert(i, *c);
+                candidates.push(inserted);
+            }
+        }
+
+        let capitalise = word.chars().next().is_some_and(char::is_uppercase);
+        let mut suggestions = Vec::new();
+        for candidate in candidates {
+            let mut suggestion: String = candidate.into_iter().collect();
+            if capitalise {
+                suggestion = capitalise_first(&suggestion);
+            }
+            if suggestion != word
+                && self.contains(&suggestion)
+                && !suggestions.contains(&suggestion)
+            {
+                suggestions.push(suggestion);
+                if suggestions.len() == MAX_SUGGESTIONS {
+                    break;
+                }
+            }
+        }
+        suggestions
+    }
+}
+
+fn capitalise_first(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref DICTIONARIES: std::sync::Mutex<HashMap<String, Arc<Dictionary>>> =
+        std::sync::Mutex::new(HashMap::new());
+}
+
+// Dictionaries ship as app resources and are parsed once per language; None when the
+// resources for the language aren't there
+async fn load_dictionary(
+    app: &AppHandle,
+    language: &str,
+) -> Result<Option<Arc<Dictionary>>, String> {
+    if language.is_empty()
+        || !language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("Invalid dictionary name: {}", language));
+    }
+    if let Some(dictionary) = DICTIONARIES
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(language)
+    {
+        return Ok(Some(dictionary.clone()));
+    }
+
+    let folder = app
+        .path()
+        .resource_dir()
+        .map_err(|e| e.to_string())?
+        .join("dictionaries");
+    let read = |extension: &str| {
+        let path = folder.join(format!("{}.{}", language, extension));
+        async move {
+            match tokio::fs::read(&path).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(format!(
+                    "Could not read dictionary {}: {}",
+                    path.display(),
+                    e
+                )),
+            }
+        }
+    };
+    let (Some(aff), Some(dic)) = (read("aff").await?, read("dic").await?) else {
+        return Ok(None);
+    };
+
+    let dictionary = Arc::new(Dictionary::parse(&aff, &dic));
+    DICTIONARIES
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(language.to_string(), dictionary.clone());
+    Ok(Some(dictionary))
+}
+
+struct Token {
+    text: String, // Curly apostrophes straightened
+    line: usize,
+    start: usize, // Byte columns, 1-based and inclusive
+    end: usize,
+    block: usize, // Paragraph, heading or table cell the word is in
+}
+
+// Words in the prose of the document. Code blocks, inline code, HTML, link targets and
+// URLs written out in the text are left out.
+fn prose_tokens(markdown: &str, lines: &Lines) -> Vec<Token> {
+    let mut options = markdown_options();
+    options.extension.autolink = false;
+    let arena = Arena::new();
+    let root = parse_document(&arena, markdown, &options);
+
+    let mut tokens = Vec::new();
+    let mut block = 0;
+    for node in root.descendants() {
+        let data = node.data();
+        match &data.value {
+            NodeValue::Paragraph | NodeValue::Heading(_) | NodeValue::TableCell => block += 1,
+            NodeValue::Text(_) if data.sourcepos.start.line == data.sourcepos.end.line => {
+                let position = data.sourcepos;
+                let line = lines.get(position.start.line);
+                let offset = position.start.column.saturating_sub(1);
+                let Some(source) = line.get(offset..position.end.column.min(line.len())) else {
+                    continue;
+                };
+                let urls = find_urls(source);
+
+                let mut word_start: Option<usize> = None;
+                let mut chars = source.char_indices().peekable();
+                while let Some((index, c)) = chars.next() {
+                    let next_is_letter = chars.peek().is_some_and(|(_, next)| next.is_alphabetic());
+                    let is_word_char = c.is_alphanumeric()
+                        || (matches!(c, '\'' | '’') && word_start.is_some() && next_is_letter);
+                    if is_word_char {
+                        word_start.get_or_insert(index);
+                    }
+                    let end = index + c.len_utf8();
+                    if let Some(start) =
+                        word_start.filter(|_| !is_word_char || chars.peek().is_none())
+                    {
+                        let end = if is_word_char { end } else { index };
+                        word_start = None;
+                        if urls
+                            .iter()
+                            .any(|(url_start, url_end)| start < *url_end && end > *url_start)
+                        {
+                            continue;
+                        }
+                        tokens.push(Token {
+                            text: source[start..end].replace('’', "'"),
+                            line: position.start.line,
+                            start: offset + start + 1,
+                            end: offset + end,
+                            block,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+// Numbers, acronyms and identifiers like camelCase aren't prose words
+fn is_checkable(word: &str) -> bool {
+    let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+    letters > 1
+        && !word.chars().any(|c| c.is_numeric())
+        && !word.chars().skip(1).any(char::is_uppercase)
+}
+
+// Only whitespace or emphasis markers between two words on the same line
+fn adjacent(first: &Token, second: &Token, lines: &Lines) -> bool {
+    if first.block != second.block {
+        return false;
+    }
+    if first.line != second.line {
+        return true;
+    }
+    lines
+        .get(first.line)
+        .get(first.end..second.start.saturating_sub(1))
+        .is_some_and(|gap| gap.chars().all(|c| c.is_whitespace() || "*_~".contains(c)))
+}
+
+fn diagnostic(
+    lines: &Lines,
+    kind: &str,
+    severity: &str,
+    first: &Token,
+    last: &Token,
+    message: String,
+    suggestions: Vec<String>,
+) -> SpellingDiagnostic {
+    let text = if first.line == last.line {
+        lines
+            .get(first.line)
+            .get(first.start - 1..last.end)
+            .unwrap_or(&first.text)
+            .to_string()
+    } else {
+        format!("{} … {}", first.text, last.text)
+    };
+    SpellingDiagnostic {
+        kind: kind.to_string(),
+        severity: severity.to_string(),
+        line: first.line,
+        column: lines.column(first.line, first.start),
+        end_line: last.line,
+        end_column: lines.column(last.line, last.end),
+        text,
+        message,
+        suggestions,
+    }
+}
+
+fn check_style(tokens: &[Token], lines: &Lines) -> Vec<SpellingDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let lower: Vec<String> = tokens
+        .iter()
+        .map(|token| token.text.to_lowercase())
+        .collect();
+
+    for i in 0..tokens.len() {
+        let next = i + 1;
+        if next < tokens.len()
+            && lower[i] == lower[next]
+            && adjacent(&tokens[i], &tokens[next], lines)
+        {
+            diagnostics.push(diagnostic(
+                lines,
+                "repeated_word",
+                "warning",
+                &tokens[i],
+                &tokens[next],
+                format!("\"{}\" is repeated", tokens[i].text),
+                vec![tokens[i].text.clone()],
+            ));
+        }
+
+        // "was written", "is being reviewed", "were quickly fixed"
+        if BE_VERBS.contains(&lower[i].as_str()) {
+            let mut j = next;
+            while j < tokens.len()
+                && (lower[j] == "being" || (lower[j].ends_with("ly") && lower[j].len() > 3))
+                && adjacent(&tokens[j - 1], &tokens[j], lines)
+            {
+                j += 1;
+            }
+            let is_participle = |word: &str| {
+                (word.ends_with("ed") && word.len() > 3) || IRREGULAR_PARTICIPLES.contains(&word)
+            };
+            if j < tokens.len()
+                && is_participle(&lower[j])
+                && adjacent(&tokens[j - 1], &tokens[j], lines)
+            {
+                diagnostics.push(diagnostic(
+                    lines,
+                    "passive_voice",
+                    "info",
+                    &tokens[i],
+                    &tokens[j],
+                    "Passive voice, consider saying who does it".to_string(),
+                    Vec::new(),
+                ));
+            }
+        }
+
+        for (phrase, replacement) in WORDY_PHRASES {
+            let words: Vec<&str> = phrase.split(' ').collect();
+            let end = i + words.len() - 1;
+            if end < tokens.len()
+                && words
+                    .iter()
+                    .enumerate()
+                    .all(|(k, word)| lower[i + k] == *word)
+                && (i..end).all(|k| adjacent(&tokens[k], &tokens[k + 1], lines))
+            {
+                diagnostics.push(diagnostic(
+                    lines,
+                    "wordy_phrase",
+                    "info",
+                    &tokens[i],
+                    &tokens[end],
+                    format!("\"{}\" can usually be \"{}\"", phrase, replacement),
+                    vec![replacement.to_string()],
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+pub fn check_markdown(
+    markdown: &str,
+    dictionary: Option<&Dictionary>,
+    custom_words: &[String],
+    style: bool,
+) -> Vec<SpellingDiagnostic> {
+    let lines = Lines::new(markdown);
+    let tokens = prose_tokens(markdown, &lines);
+    let custom: HashSet<String> = custom_words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect();
+
+    let mut diagnostics = Vec::new();
+    if let Some(dictionary) = dictionary {
+        for token in tokens.iter().filter(|token| is_checkable(&token.text)) {
+            let word = token.text.as_str();
+            let known = dictionary.contains(word)
+                || custom.contains(&word.to_lowercase())
+                || word.strip_suffix("'s").is_some_and(|base| {
+                    dictionary.contains(base) || custom.contains(&base.to_lowercase())
+                });
+            if !known {
+                diagnostics.push(diagnostic(
+                    &lines,
+                    "spelling",
+                    "error",
+                    token,
+                    token,
+                    format!("Unknown word \"{}\"", word),
+                    dictionary.suggest(word),
+                ));
+            }
+        }
+    }
+    if style {
+        diagnostics.extend(check_style(&tokens, &lines));
+    }
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    diagnostics
+}
+
+async fn check_with_profile(
+    app: &AppHandle,
+    markdown: &str,
+    options: SpellCheckOptions,
+) -> Result<Vec<SpellingDiagnostic>, String> {
+    let profile = load_current_profile(app).await?;
+    let language = options.language.as_deref().unwrap_or(DEFAULT_LANGUAGE);
+    let dictionary = if options.spelling.unwrap_or(true) {
+        load_dictionary(app, language).await?
+    } else {
+        None
+    };
+
+    let mut diagnostics = check_markdown(
+        markdown,
+        dictionary.as_deref(),
+        profile.custom_dictionary(),
+        options.style.unwrap_or(true),
+    );
+    // Without a dictionary the style checks still run, but say why spelling was skipped
+    if options.spelling.unwrap_or(true) && dictionary.is_none() {
+        diagnostics.insert(
+            0,
+            SpellingDiagnostic {
+                kind: "dictionary_missing".to_string(),
+                severity: "warning".to_string(),
+                line: 1,
+                column: 1,
+                end_line: 1,
+                end_column: 1,
+                text: String::new(),
+                message: format!(
+                    "No {} dictionary installed, spelling was not checked",
+                    language
+                ),
+                suggestions: Vec::new(),
+            },
+        );
+    }
+    Ok(diagnostics)
+}
+
+#[tauri::command]
+pub async fn check_blog_post_spelling(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+    options: Option<SpellCheckOptions>,
+) -> Result<Vec<SpellingDiagnostic>, String> {
+    let file_name = {
+        let state = state.lock().await;
+        let pool = state.pool.as_ref().ok_or("Database not connected")?;
+        sqlx::query_scalar!(
+            "SELECT file_name FROM tadgh_blog.blog_posts WHERE id = $1",
+            blog_post_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Blog post not found")?
+    };
+
+    let blog_folder = load_current_profile(&app)
+        .await?
+        .blog_folder()
+        .ok_or("Blog folder path not configured")?;
+    let markdown = tokio::fs::read_to_string(blog_folder.join(&file_name))
+        .await
+        .map_err(|e| format!("Could not read {}: {}", file_name, e))?;
+
+    check_with_profile(&app, &markdown, options.unwrap_or_default()).await
+}
+
+// For text in the editor that hasn't been saved yet
+#[tauri::command]
+pub async fn check_markdown_spelling(
+    app: AppHandle,
+    markdown: String,
+    options: Option<SpellCheckOptions>,
+) -> Result<Vec<SpellingDiagnostic>, String> {
+    check_with_profile(&app, &markdown, options.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn get_custom_dictionary(app: AppHandle) -> Result<Vec<String>, String> {
+    Ok(load_current_profile(&app)
+        .await?
+        .custom_dictionary()
+        .to_vec())
+}
+
+#[tauri::command]
+pub async fn add_custom_dictionary_word(
+    app: AppHandle,
+    word: String,
+) -> Result<Vec<String>, String> {
+    let word = word.trim().to_string();
+    if word.is_empty() || word.contains(char::is_whitespace) {
+        return Err("Custom dictionary entries must be a single word".to_string());
+    }
+
+    let mut profile = load_current_profile(&app).await?;
+    let mut words = profile.custom_dictionary().to_vec();
+    if !words
+        .iter()
+        .any(|existing| existing.eq_ignore_ascii_case(&word))
+    {
+        words.push(word);
+        words.sort_by_key(|word| word.to_lowercase());
+    }
+    profile.set_custom_dictionary(words.clone());
+    save_profile(profile, app).await?;
+
+    Ok(words)
+}
+
+#[tauri::command]
+pub async fn remove_custom_dictionary_word(
+    app: AppHandle,
+    word: String,
+) -> Result<Vec<String>, String> {
+    let mut profile = load_current_profile(&app).await?;
+    let words: Vec<String> = profile
+        .custom_dictionary()
+        .iter()
+        .filter(|existing| !existing.eq_ignore_ascii_case(word.trim()))
+        .cloned()
+        .collect();
+    profile.set_custom_dictionary(words.clone());
+    save_profile(profile, app).await?;
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> Dictionary {
+        Dictionary::parse(
+            include_bytes!("../tests/fixtures/spelling/test.aff"),
+            include_bytes!("../tests/fixtures/spelling/test.dic"),
+        )
+    }
+
+    #[test]
+    fn suffix_conditions_match_the_end_of_the_stem() {
+        let dictionary = fixture();
+        for word in ["cities", "days", "boxes", "baked", "locked"] {
+            assert!(dictionary.contains(word), "{} should be a word", word);
+        }
+        for word in ["citys", "daies", "boxs", "bakeed", "bakes"] {
+            assert!(!dictionary.contains(word), "{} should not be a word", word);
+        }
+    }
+
+    #[test]
+    fn prefixes_combine_with_cross_product_suffixes_only() {
+        let dictionary = fixture();
+        for word in ["unlock", "unpack", "unpacks", "repack", "packs"] {
+            assert!(dictionary.contains(word), "{} should be a word", word);
+        }
+        // D and R don't allow cross products
+        for word in ["unlocked", "repacks"] {
+            assert!(!dictionary.contains(word), "{} should not be a word", word);
+        }
+    }
+
+    #[test]
+    fn needaffix_stems_are_not_words() {
+        assert!(!fixture().contains("ness"));
+    }
+
+    #[test]
+    fn title_case_matches_lowercase_entries() {
+        let dictionary = fixture();
+        assert!(dictionary.contains("Cities"));
+        assert_eq!(dictionary.suggest("Citty"), vec!["City".to_string()]);
+    }
+}
@@ -1,14 +1,131 @@
 use serde::{Deserialize, Serialize};
-
+use sqlx::PgConnection;
 use tauri::State;
 use tokio::sync::Mutex;
 
+use crate::slugs::{change_slug, resolve_slug};
+use crate::validation::{validate_tag, Validate, Validator};
 use crate::AppState;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Tag {
     pub id: i32,
     pub name: String,
+    pub slug: String,
+}
+
+// The tags a post or project should end up with: existing tags by id plus tags by
+// name, which are matched case-insensitively and created when missing
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TagSelection {
+    pub tag_ids: Option<Vec<i32>>,
+    pub tag_names: Option<Vec<String>>,
+}
+
+impl Validate for TagSelection {
+    fn rules(&self, v: &mut Validator) {
+        for name in self.tag_names.iter().flatten() {
+            v.required("tag_names", name)
+                .max_length("tag_names", Some(name.trim()), 50);
+        }
+    }
+}
+
+pub async fn resolve_tag_selection(
+    conn: &mut PgConnection,
+    selection: &TagSelection,
+) -> Result<Vec<i32>, String> {
+    let mut tag_ids = selection.tag_ids.clone().unwrap_or_default();
+
+    for name in selection.tag_names.iter().flatten() {
+        let name = name.trim();
+        let existing = sqlx::query_scalar!(
+            "SELECT id FROM tadgh_blog.tags WHERE LOWER(name) = LOWER($1)",
+            name
+        )
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let tag_id = match existing {
+            Some(id) => id,
+            None => {
+                let slug = resolve_slug(conn, "tag", None, name, None).await?;
+                sqlx::query_scalar!(
+                    "INSERT INTO tadgh_blog.tags (name, slug) VALUES ($1, $2) RETURNING id",
+                    name,
+                    slug
+                )
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?
+            }
+        };
+
+        if !tag_ids.contains(&tag_id) {
+            tag_ids.push(tag_id);
+        }
+    }
+
+    Ok(tag_ids)
+}
+
+pub async fn replace_blog_post_tags(
+    conn: &mut PgConnection,
+    blog_post_id: i32,
+    tag_ids: &[i32],
+) -> Result<(), String> {
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.blog_post_tags WHERE blog_post_id = $1",
+        blog_post_id
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tadgh_blog.blog_post_tags (blog_post_id, tag_id)
+        SELECT $1, UNNEST($2::int[])
+        ON CONFLICT DO NOTHING
+        "#,
+        blog_post_id,
+        tag_ids
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub async fn replace_project_tags(
+    conn: &mut PgConnection,
+    project_id: i32,
+    tag_ids: &[i32],
+) -> Result<(), String> {
+    sqlx::query!(
+        "DELETE FROM tadgh_blog.project_tags WHERE project_id = $1",
+        project_id
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tadgh_blog.project_tags (project_id, tag_id)
+        SELECT $1, UNNEST($2::int[])
+        ON CONFLICT DO NOTHING
+        "#,
+        project_id,
+        tag_ids
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -18,7 +135,7 @@ pub async fn get_tags(state: State<'_, Mutex<AppState>>) -> Result<Vec<Tag>, Str
     sqlx::query_as!(
         Tag,
         r#"
-        SELECT id, name
+        SELECT id, name, slug
         FROM tadgh_blog.tags
         ORDER BY name ASC
         "#
@@ -29,21 +146,35 @@ pub async fn get_tags(state: State<'_, Mutex<AppState>>) -> Result<Vec<Tag>, Str
 }
 
 #[tauri::command]
-pub async fn create_tag(state: State<'_, Mutex<AppState>>, name: String) -> Result<Tag, String> {
+pub async fn create_tag(
+    state: State<'_, Mutex<AppState>>,
+    name: String,
+    slug: Option<String>,
+) -> Result<Tag, String> {
+    validate_tag(&name, slug.as_deref())?;
     let state = state.lock().await;
     let pool = state.pool.as_ref().ok_or("Database not connected")?;
-    sqlx::query_as!(
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let slug = resolve_slug(&mut tx, "tag", slug.as_deref(), &name, None).await?;
+
+    let tag = sqlx::query_as!(
         Tag,
         r#"
-        INSERT INTO tadgh_blog.tags (name)
-        VALUES ($1)
-        RETURNING id, name
+        INSERT INTO tadgh_blog.tags (name, slug)
+        VALUES ($1, $2)
+        RETURNING id, name, slug
         "#,
-        name
+        name,
+        slug
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| e.to_string())
+    .map_err(|e| e.to_string())?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(tag)
 }
 
 #[tauri::command]
@@ -170,7 +301,7 @@ pub async fn get_blog_tags(
     sqlx::query_as!(
         Tag,
         r#"
-        SELECT t.id, t.name
+        SELECT t.id, t.name, t.slug
         FROM tadgh_blog.tags t
         JOIN tadgh_blog.blog_post_tags bpt ON bpt.tag_id = t.id
         WHERE bpt.blog_post_id = $1
@@ -193,7 +324,7 @@ pub async fn get_project_tags(
     sqlx::query_as!(
         Tag,
         r#"
-        SELECT t.id, t.name
+        SELECT t.id, t.name, t.slug
         FROM tadgh_blog.tags t
         JOIN tadgh_blog.project_tags pt ON pt.tag_id = t.id
         WHERE pt.project_id = $1
@@ -211,9 +342,13 @@ pub async fn update_tag(
     state: State<'_, Mutex<AppState>>,
     id: i32,
     name: String,
+    slug: Option<String>,
 ) -> Result<(), String> {
+    validate_tag(&name, slug.as_deref())?;
     let state = state.lock().await;
     let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
     sqlx::query!(
         r#"
         UPDATE tadgh_blog.tags
@@ -223,10 +358,16 @@ pub async fn update_tag(
         name,
         id
     )
-    .execute(pool)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
+    if let Some(slug) = slug {
+        change_slug(&mut tx, "tag", id, &slug).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
     Ok(())
 }
 
@@ -0,0 +1,434 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, State};
+use tokio::sync::Mutex;
+
+use crate::settings::load_current_profile;
+use crate::slugs::{localized_permalink, record_redirect, resolve_slug};
+use crate::validation::{Validate, Validator};
+use crate::watcher::FolderWatcher;
+use crate::AppState;
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Translation {
+    pub id: i32,
+    pub blog_post_id: i32,
+    pub locale: String,
+    pub title: String,
+    pub description: String,
+    pub file_name: String,
+    pub slug: String,
+    pub published: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Anything left out is copied from the source post, ready to be translated
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTranslation {
+    pub locale: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub file_name: Option<String>, // Defaults to `<source name>.<locale>.md`
+    pub slug: Option<String>,      // Generated from the title when not given
+    pub copy_markdown: Option<bool>, // Start from a copy of the source file, on by default
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTranslation {
+    pub id: i32,
+    pub title: String,
+    pub description: String,
+    pub file_name: String,
+    pub slug: Option<String>, // Left out to keep the current slug
+    pub published: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MissingTranslation {
+    pub blog_post_id: i32,
+    pub title: String,
+    pub slug: Option<String>,
+    pub locale: String, // Language the post is written in
+    pub created: Option<NaiveDate>,
+    pub published: bool,
+    pub translated_locales: Vec<String>,
+}
+
+impl Validate for CreateTranslation {
+    fn rules(&self, v: &mut Validator) {
+        v.max_length("title", self.title.as_deref(), 200)
+            .max_length("description", self.description.as_deref(), 1000)
+            .file_name("file_name", self.file_name.as_deref())
+            .slug("slug", self.slug.as_deref());
+    }
+}
+
+impl Validate for UpdateTranslation {
+    fn rules(&self, v: &mut Validator) {
+        v.required("title", &self.title)
+            .max_length("title", Some(&self.title), 200)
+            .required("description", &self.description)
+            .max_length("description", Some(&self.description), 1000)
+            .required("file_name", &self.file_name)
+            .file_name("file_name", Some(&self.file_name))
+            .slug("slug", self.slug.as_deref());
+    }
+}
+
+// Language tags like "fr" or "fr-CA", normalised to lowercase language and uppercase region
+fn normalize_locale(locale: &str) -> Result<String, String> {
+    let locale = locale.trim().replace('_', "-");
+    let (language, region) = match locale.split_once('-') {
+        Some((language, region)) => (language, Some(region)),
+        None => (locale.as_str(), None),
+    };
+    let valid_language =
+        (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_alphabetic());
+    let valid_region = region
+        .is_none_or(|region| region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()));
+    if !valid_language || !valid_region {
+        return Err(format!(
+            "Invalid locale: {}, use e.g. \"fr\" or \"fr-CA\"",
+            locale
+        ));
+    }
+
+    Ok(match region {
+        Some(region) => format!("{}-{}", language.to_lowercase(), region.to_uppercase()),
+        None => language.to_lowercase(),
+    })
+}
+
+// `my-post.md` becomes `my-post.fr.md`
+fn localized_file_name(file_name: &str, locale: &str) -> String {
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("md");
+    format!("{}.{}.{}", stem, locale, extension)
+}
+
+#[tauri::command]
+pub async fn get_blog_post_translations(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+) -> Result<Vec<Translation>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        Translation,
+        r#"
+        SELECT id, blog_post_id, locale, title, description, file_name, slug, published,
+               created_at, updated_at
+        FROM tadgh_blog.blog_post_translations
+        WHERE blog_post_id = $1
+        ORDER BY locale ASC
+        "#,
+        blog_post_id
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+// Sets the language the post itself is written in
+#[tauri::command]
+pub async fn set_blog_post_locale(
+    state: State<'_, Mutex<AppState>>,
+    blog_post_id: i32,
+    locale: String,
+) -> Result<String, String> {
+    let locale = normalize_locale(&locale)?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let clashes = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM tadgh_blog.blog_post_translations
+            WHERE blog_post_id = $1 AND locale = $2
+        ) as "clashes!"
+        "#,
+        blog_post_id,
+        locale
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if clashes {
+        return Err(format!("The post already has a {} translation", locale));
+    }
+
+    let result = sqlx::query!(
+        "UPDATE tadgh_blog.blog_posts SET locale = $1 WHERE id = $2",
+        locale,
+        blog_post_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err("Blog post not found".to_string());
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(locale)
+}
+
+// Creates a draft translation of a post. The source markdown is copied to the new
+// file unless that file already exists, so translators can work over the original.
+#[tauri::command]
+pub async fn create_translation(
+    app: AppHandle,
+    state: State<'_, Mutex<AppState>>,
+    watcher: State<'_, FolderWatcher>,
+    blog_post_id: i32,
+    translation: CreateTranslation,
+) -> Result<Translation, String> {
+    translation.validate()?;
+    let locale = normalize_locale(&translation.locale)?;
+    let copy_markdown = translation.copy_markdown.unwrap_or(true);
+    let blog_folder = load_current_profile(&app).await?.blog_folder();
+    if copy_markdown && blog_folder.is_none() {
+        return Err("Blog folder path not configured".to_string());
+    }
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let source = sqlx::query!(
+        r#"
+        SELECT bp.title, bp.description, bp.file_name, bp.locale,
+               EXISTS (
+                   SELECT 1 FROM tadgh_blog.blog_post_translations t
+                   WHERE t.blog_post_id = bp.id AND t.locale = $2
+               ) as "translated!"
+        FROM tadgh_blog.blog_posts bp
+        WHERE bp.id = $1
+        FOR UPDATE OF bp
+        "#,
+        blog_post_id,
+        locale
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Blog post not found")?;
+
+    if source.locale == locale {
+        return Err(format!("The post is already written in {}", locale));
+    }
+    if source.translated {
+        return Err(format!("The post already has a {} translation", locale));
+    }
+
+    let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+    let title = non_empty(translation.title).unwrap_or(source.title);
+    let description = non_empty(translation.description).unwrap_or(source.description);
+    let file_name = non_empty(translation.file_name)
+        .unwrap_or_else(|| localized_file_name(&source.file_name, &locale));
+    if file_name == source.file_name {
+        return Err("A translation needs its own markdown file".to_string());
+    }
+
+    let slug = resolve_slug(
+        &mut tx,
+        "translation",
+        translation.slug.as_deref(),
+        &title,
+        None,
+    )
+    .await?;
+
+    let created = sqlx::query_as!(
+        Translation,
+        r#"
+        INSERT INTO tadgh_blog.blog_post_translations
+        (blog_post_id, locale, title, description, file_name, slug)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id, blog_post_id, locale, title, description, file_name, slug, published,
+                  created_at, updated_at
+        "#,
+        blog_post_id,
+        locale,
+        title,
+        description,
+        file_name,
+        slug
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Copied once the row is in, and removed again if the commit fails
+    let mut copied_markdown = None;
+    if let (true, Some(folder)) = (copy_markdown, &blog_folder) {
+        let target = folder.join(&created.file_name);
+        if !target.exists() {
+            let writing = watcher.expect_write(&created.file_name);
+            tokio::fs::copy(folder.join(&source.file_name), &target)
+                .await
+                .map_err(|e| format!("Could not copy {}: {}", source.file_name, e))?;
+            copied_markdown = Some((target, writing));
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        if let Some((path, _)) = &copied_markdown {
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        return Err(e.to_string());
+    }
+
+    Ok(created)
+}
+
+#[tauri::command]
+pub async fn update_translation(
+    state: State<'_, Mutex<AppState>>,
+    translation: UpdateTranslation,
+) -> Result<Translation, String> {
+    translation.validate()?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let previous = sqlx::query!(
+        "SELECT locale, slug FROM tadgh_blog.blog_post_translations WHERE id = $1 FOR UPDATE",
+        translation.id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Translation not found")?;
+
+    let slug = match translation.slug.as_deref() {
+        Some(requested) if requested != previous.slug => {
+            resolve_slug(
+                &mut tx,
+                "translation",
+                Some(requested),
+                &translation.title,
+                Some(translation.id),
+            )
+            .await?
+        }
+        _ => previous.slug.clone(),
+    };
+
+    let updated = sqlx::query_as!(
+        Translation,
+        r#"
+        UPDATE tadgh_blog.blog_post_translations
+        SET title = $1,
+            description = $2,
+            file_name = $3,
+            slug = $4,
+            published = $5,
+            updated_at = NOW()
+        WHERE id = $6
+        RETURNING id, blog_post_id, locale, title, description, file_name, slug, published,
+                  created_at, updated_at
+        "#,
+        translation.title,
+        translation.description,
+        translation.file_name,
+        slug,
+        translation.published,
+        translation.id
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if slug != previous.slug {
+        record_redirect(
+            &mut tx,
+            &localized_permalink(&previous.locale, &previous.slug),
+            &localized_permalink(&updated.locale, &slug),
+            "translation",
+            updated.id,
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+// The translation's markdown file is left in the blog folder
+#[tauri::command]
+pub async fn delete_translation(state: State<'_, Mutex<AppState>>, id: i32) -> Result<(), String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let result = sqlx::query!(
+        "DELETE FROM tadgh_blog.blog_post_translations WHERE id = $1",
+        id
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if result.rows_affected() == 0 {
+        return Err("Translation not found".to_string());
+    }
+    Ok(())
+}
+
+// Posts written in another language that have no translation into `locale` yet,
+// newest first. Drafts are left out unless asked for.
+#[tauri::command]
+pub async fn get_missing_translations(
+    state: State<'_, Mutex<AppState>>,
+    locale: String,
+    include_drafts: Option<bool>,
+) -> Result<Vec<MissingTranslation>, String> {
+    let locale = normalize_locale(&locale)?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    sqlx::query_as!(
+        MissingTranslation,
+        r#"
+        SELECT
+            bp.id as blog_post_id,
+            bp.title,
+            bp.slug,
+            bp.locale,
+            bp.created,
+            bp.published,
+            COALESCE(
+                ARRAY_AGG(t.locale ORDER BY t.locale) FILTER (WHERE t.id IS NOT NULL),
+                '{}'
+            ) as "translated_locales!"
+        FROM tadgh_blog.blog_posts bp
+        LEFT JOIN tadgh_blog.blog_post_translations t ON t.blog_post_id = bp.id
+        WHERE bp.locale <> $1
+          AND (bp.published OR $2)
+          AND NOT EXISTS (
+              SELECT 1 FROM tadgh_blog.blog_post_translations missing
+              WHERE missing.blog_post_id = bp.id AND missing.locale = $1
+          )
+        GROUP BY bp.id
+        ORDER BY bp.created DESC NULLS LAST, bp.id DESC
+        "#,
+        locale,
+        include_drafts.unwrap_or(false)
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,177 @@
+use chrono::{Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::slugs::slugify;
+
+const EARLIEST_YEAR: i32 = 1990;
+const MAX_MONTHS_AHEAD: u32 = 12; // Leaves room for posts dated for a scheduled release
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+// Sent to the frontend as the JSON error string of a failed command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidationErrors {
+    pub field_errors: Vec<FieldError>,
+}
+
+// Implemented by command inputs, listing the rules each field must pass
+pub trait Validate {
+    fn rules(&self, v: &mut Validator);
+
+    fn validate(&self) -> Result<(), String> {
+        let mut v = Validator::default();
+        self.rules(&mut v);
+        v.finish()
+    }
+}
+
+// Collects every failing field instead of stopping at the first, so a form can mark them all
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    fn fail(&mut self, field: &str, message: impl Into<String>) {
+        // One message per field is enough for a form
+        if !self.errors.iter().any(|error| error.field == field) {
+            self.errors.push(FieldError {
+                field: field.to_string(),
+                message: message.into(),
+            });
+        }
+    }
+
+    pub fn required(&mut self, field: &str, value: &str) -> &mut Self {
+        if value.trim().is_empty() {
+            self.fail(field, "Required");
+        }
+        self
+    }
+
+    pub fn max_length(&mut self, field: &str, value: Option<&str>, max: usize) -> &mut Self {
+        if value.is_some_and(|value| value.chars().count() > max) {
+            self.fail(field, format!("Must be at most {} characters", max));
+        }
+        self
+    }
+
+    // A bare file name inside the configured folder, never a path out of it
+    pub fn file_name(&mut self, field: &str, value: Option<&str>) -> &mut Self {
+        let Some(value) = value.filter(|value| !value.is_empty()) else {
+            return self;
+        };
+        if value.contains(['/', '\\']) || value == "." || value == ".." {
+            self.fail(field, "Must be a file name without folders");
+        } else if value.trim() != value || value.chars().any(char::is_control) {
+            self.fail(
+                field,
+                "Must not start or end with spaces or contain control characters",
+            );
+        } else if value.contains([':', '*', '?', '"', '<', '>', '|']) {
+            self.fail(field, "Must not contain : * ? \" < > or |");
+        }
+        self
+    }
+
+    pub fn url(&mut self, field: &str, value: Option<&str>) -> &mut Self {
+        let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+            return self;
+        };
+        let host = value
+            .strip_prefix("https://")
+            .or_else(|| value.strip_prefix("http://"))
+            .and_then(|rest| rest.split(['/', '?', '#']).next())
+            .unwrap_or_default();
+        let host_name = host.rsplit_once(':').map_or(host, |(name, _)| name);
+        if host_name.is_empty() || value.chars().any(char::is_whitespace) {
+            self.fail(field, "Must be a full http:// or https:// URL");
+        }
+        self
+    }
+
+    // A path on the site itself, e.g. `/blog/my-post`
+    pub fn site_path(&mut self, field: &str, value: Option<&str>) -> &mut Self {
+        let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+            return self;
+        };
+        if !value.starts_with('/') || value.starts_with("//") {
+            self.fail(field, "Must be a path starting with a single /");
+        } else if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            self.fail(field, "Must not contain spaces");
+        }
+        self
+    }
+
+    pub fn between(&mut self, field: &str, value: Option<f32>, min: f32, max: f32) -> &mut Self {
+        if value.is_some_and(|value| !(min..=max).contains(&value)) {
+            self.fail(field, format!("Must be between {} and {}", min, max));
+        }
+        self
+    }
+
+    pub fn one_of(&mut self, field: &str, value: Option<i32>, allowed: &[i32]) -> &mut Self {
+        if value.is_some_and(|value| !allowed.contains(&value)) {
+            let allowed: Vec<String> = allowed.iter().map(i32::to_string).collect();
+            self.fail(field, format!("Must be one of {}", allowed.join(", ")));
+        }
+        self
+    }
+
+    pub fn date(&mut self, field: &str, value: Option<NaiveDate>) -> &mut Self {
+        let Some(value) = value else {
+            return self;
+        };
+        let latest = Utc::now().date_naive() + Months::new(MAX_MONTHS_AHEAD);
+        if value.year() < EARLIEST_YEAR {
+            self.fail(field, format!("Must be in {} or later", EARLIEST_YEAR));
+        } else if value > latest {
+            self.fail(field, format!("Must be no later than {}", latest));
+        }
+        self
+    }
+
+    // Slugs are normalised on save, but there has to be something left to normalise
+    pub fn slug(&mut self, field: &str, value: Option<&str>) -> &mut Self {
+        if value.is_some_and(|value| !value.trim().is_empty() && slugify(value).is_empty()) {
+            self.fail(field, "Must contain letters or numbers");
+        }
+        self
+    }
+
+    pub fn finish(&mut self) -> Result<(), String> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let errors = ValidationErrors {
+            field_errors: std::mem::take(&mut self.errors),
+        };
+        Err(serde_json::to_string(&errors).map_err(|e| e.to_string())?)
+    }
+}
+
+pub fn validate_tag(name: &str, slug: Option<&str>) -> Result<(), String> {
+    Validator::default()
+        .required("name", name)
+        .max_length("name", Some(name), 50)
+        .slug("slug", slug)
+        .finish()
+}
+
+// Categories and series share the same shape
+pub fn validate_named(
+    name: &str,
+    description: Option<&str>,
+    slug: Option<&str>,
+) -> Result<(), String> {
+    Validator::default()
+        .required("name", name)
+        .max_length("name", Some(name), 100)
+        .max_length("description", description, 1000)
+        .slug("slug", slug)
+        .finish()
+}
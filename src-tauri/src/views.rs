@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 use tokio::sync::Mutex;
 
+use crate::listing::{LinkTable, ListOptions, Listing, Page};
 use crate::AppState;
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -12,7 +13,7 @@ pub struct BlogPostView {
     pub viewed_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct BlogPostWithViews {
     pub id: i32,
     pub title: String,
@@ -23,16 +24,8 @@ pub struct BlogPostWithViews {
     pub view_count: i64,
 }
 
-#[tauri::command]
-pub async fn get_blog_posts_with_views(
-    state: State<'_, Mutex<AppState>>,
-) -> Result<Vec<BlogPostWithViews>, String> {
-    let state = state.lock().await;
-    let pool = state.pool.as_ref().ok_or("Database not connected")?;
-
-    sqlx::query_as!(
-        BlogPostWithViews,
-        r#"
+const BLOG_POSTS_WITH_VIEWS_LISTING: Listing = Listing {
+    source: r#"
         SELECT
             bp.id,
             bp.title,
@@ -40,16 +33,69 @@ pub async fn get_blog_posts_with_views(
             bp.description,
             bp.image_name,
             bp.file_name,
-            COALESCE(COUNT(bpi.id), 0) as "view_count!"
+            COUNT(bpi.id) as view_count
         FROM tadgh_blog.blog_posts bp
         LEFT JOIN tadgh_blog.blog_post_ips bpi ON bp.id = bpi.blog_post_id
         GROUP BY bp.id, bp.title, bp.created, bp.description, bp.image_name, bp.file_name
-        ORDER BY bp.created DESC
-        "#
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(|e| e.to_string())
+    "#,
+    date_column: Some("created"),
+    text_columns: &["title", "description", "file_name"],
+    tag_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_tags",
+        column: "blog_post_id",
+        owner: "id",
+    }),
+    author_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_authors",
+        column: "blog_post_id",
+        owner: "id",
+    }),
+    status_column: None,
+    sort_fields: &[
+        ("created", "date"),
+        ("title", "text"),
+        ("view_count", "int8"),
+        ("id", "int4"),
+    ],
+    default_sort: ("created", true),
+};
+
+const BLOG_POST_VIEW_LISTING: Listing = Listing {
+    source: r#"
+        SELECT id, blog_post_id, ip_address, viewed_at
+        FROM tadgh_blog.blog_post_ips
+    "#,
+    date_column: Some("viewed_at"),
+    text_columns: &["ip_address"],
+    tag_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_tags",
+        column: "blog_post_id",
+        owner: "blog_post_id",
+    }),
+    author_link: Some(LinkTable {
+        table: "tadgh_blog.blog_post_authors",
+        column: "blog_post_id",
+        owner: "blog_post_id",
+    }),
+    status_column: None,
+    sort_fields: &[
+        ("id", "int4"),
+        ("viewed_at", "timestamptz"),
+        ("ip_address", "text"),
+    ],
+    default_sort: ("id", true),
+};
+
+#[tauri::command]
+pub async fn get_blog_posts_with_views(
+    state: State<'_, Mutex<AppState>>,
+    options: Option<ListOptions>,
+) -> Result<Page<BlogPostWithViews>, String> {
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    BLOG_POSTS_WITH_VIEWS_LISTING
+        .fetch(pool, options, None)
+        .await
 }
 
 #[tauri::command]
@@ -124,21 +170,11 @@ pub async fn add_multiple_views_to_blog_post(
 pub async fn get_blog_post_views(
     state: State<'_, Mutex<AppState>>,
     blog_post_id: i32,
-) -> Result<Vec<BlogPostView>, String> {
+    options: Option<ListOptions>,
+) -> Result<Page<BlogPostView>, String> {
     let state = state.lock().await;
     let pool = state.pool.as_ref().ok_or("Database not connected")?;
-
-    sqlx::query_as!(
-        BlogPostView,
-        r#"
-        SELECT id, blog_post_id, ip_address, viewed_at
-        FROM tadgh_blog.blog_post_ips
-        WHERE blog_post_id = $1
-        ORDER BY id DESC
-        "#,
-        blog_post_id
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(|e| e.to_string())
+    BLOG_POST_VIEW_LISTING
+        .fetch(pool, options, Some(("blog_post_id", blog_post_id)))
+        .await
 }
@@ -0,0 +1,489 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::blog::insert_draft_from_file;
+use crate::scheduler::current_pool;
+use crate::search::index_blog_body;
+use crate::settings::{load_current_profile, Profile};
+use crate::slugs::{file_permalink, permalink, record_redirect};
+use crate::validation::Validator;
+use crate::AppState;
+
+// How often the blog and images folders are compared against the last scan
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Emitted as "folder-change" for every file created, modified, deleted or renamed in
+// the blog or images folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderChange {
+    pub folder: String, // "posts" or "images"
+    pub kind: String,   // "created", "modified", "deleted" or "renamed"
+    pub file_name: String,
+    pub previous_file_name: Option<String>, // Set for renames
+    pub blog_post_ids: Vec<i32>,            // Posts using the file, or the draft created for it
+    pub created_post: bool,
+}
+
+// A post whose markdown changed on disk, kept until it is dismissed or relinked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileFlag {
+    pub blog_post_id: i32,
+    pub title: String,
+    pub file_name: String,
+    pub kind: String,               // "missing", "modified" or "renamed"
+    pub renamed_to: Option<String>, // New file name when the file was renamed
+    pub detected_at: DateTime<Utc>,
+}
+
+// Flags raised by the background watcher for the active profile
+#[derive(Default)]
+pub struct FolderWatcher {
+    flags: Mutex<HashMap<i32, FileFlag>>,
+    // Markdown files the app is writing itself, with when they stop being ignored
+    writing: std::sync::Mutex<HashMap<String, Option<Instant>>>,
+}
+
+// Held while the app writes a file in the blog folder so the watcher doesn't treat it
+// as an outside change, e.g. by creating a draft for a duplicate before its post is
+// committed. The file stays ignored for a couple of polls after this is dropped.
+pub struct PendingWrite<'a> {
+    watcher: &'a FolderWatcher,
+    file_name: String,
+}
+
+impl FolderWatcher {
+    pub fn expect_write(&self, file_name: &str) -> PendingWrite<'_> {
+        if let Ok(mut writing) = self.writing.lock() {
+            writing.insert(file_name.to_string(), None);
+        }
+        PendingWrite {
+            watcher: self,
+            file_name: file_name.to_string(),
+        }
+    }
+
+    fn is_app_write(&self, file_name: &str) -> bool {
+        let Ok(mut writing) = self.writing.lock() else {
+            return false;
+        };
+        let now = Instant::now();
+        writing.retain(|_, until| until.is_none_or(|until| until > now));
+        writing.contains_key(file_name)
+    }
+}
+
+impl Drop for PendingWrite<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut writing) = self.watcher.writing.lock() {
+            writing.insert(
+                self.file_name.clone(),
+                Some(Instant::now() + POLL_INTERVAL * 2),
+            );
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct FileState {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+struct Snapshot {
+    folder: PathBuf,
+    files: HashMap<String, FileState>,
+}
+
+struct Change {
+    kind: &'static str,
+    file_name: String,
+    previous_file_name: Option<String>,
+}
+
+// Top-level files, without hidden files and editor backups like `.post.md.swp` or `post.md~`
+async fn scan(folder: &Path) -> Result<HashMap<String, FileState>, String> {
+    let mut entries = tokio::fs::read_dir(folder)
+        .await
+        .map_err(|e| format!("Could not read {}: {}", folder.display(), e))?;
+
+    let mut files = HashMap::new();
+    while let Some(entry) = entries.next_entry().await.map_err(|e| e.to_string())? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if name.starts_with('.') || name.ends_with('~') {
+            continue;
+        }
+        let metadata = entry.metadata().await.map_err(|e| e.to_string())?;
+        if metadata.is_file() {
+            files.insert(
+                name,
+                FileState {
+                    modified: metadata.modified().ok(),
+                    len: metadata.len(),
+                },
+            );
+        }
+    }
+    Ok(files)
+}
+
+// A file that disappeared while another with the same size and modification time
+// appeared is treated as renamed, since renaming keeps both
+fn diff(before: &HashMap<String, FileState>, after: &HashMap<String, FileState>) -> Vec<Change> {
+    let mut created: Vec<&String> = after
+        .keys()
+        .filter(|name| !before.contains_key(*name))
+        .collect();
+    let mut deleted: Vec<&String> = before
+        .keys()
+        .filter(|name| !after.contains_key(*name))
+        .collect();
+    created.sort();
+    deleted.sort();
+
+    let mut changes = Vec::new();
+    for old in deleted {
+        let state = before[old];
+        let renamed = created
+            .iter()
+            .position(|name| state.modified.is_some() && after[*name] == state);
+        match renamed {
+            Some(index) => changes.push(Change {
+                kind: "renamed",
+                file_name: created.remove(index).clone(),
+                previous_file_name: Some(old.clone()),
+            }),
+            None => changes.push(Change {
+                kind: "deleted",
+                file_name: old.clone(),
+                previous_file_name: None,
+            }),
+        }
+    }
+    for name in created {
+        changes.push(Change {
+            kind: "created",
+            file_name: name.clone(),
+            previous_file_name: None,
+        });
+    }
+
+    let mut modified: Vec<&String> = after
+        .iter()
+        .filter(|(name, state)| before.get(*name).is_some_and(|old| old != *state))
+        .map(|(name, _)| name)
+        .collect();
+    modified.sort();
+    for name in modified {
+        changes.push(Change {
+            kind: "modified",
+            file_name: name.clone(),
+            previous_file_name: None,
+        });
+    }
+    changes
+}
+
+// Background loop started from `setup`. Folders are polled rather than watched so
+// network drives and synced folders behave the same as local ones.
+pub async fn run(app: AppHandle) {
+    let mut snapshots: HashMap<&'static str, Snapshot> = HashMap::new();
+    loop {
+        if let Err(e) = poll(&app, &mut snapshots).await {
+            let _ = app.emit("folder-watch-error", e);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+async fn poll(
+    app: &AppHandle,
+    snapshots: &mut HashMap<&'static str, Snapshot>,
+) -> Result<(), String> {
+    // Changes can only be reconciled against a database
+    let Some(pool) = current_pool(app).await else {
+        snapshots.clear();
+        return Ok(());
+    };
+    let profile = load_current_profile(app).await?;
+    let folders = [
+        ("posts", profile.blog_folder()),
+        ("images", profile.blog_images_folder()),
+    ];
+
+    for (name, folder) in folders {
+        let Some(folder) = folder else {
+            snapshots.remove(name);
+            continue;
+        };
+        // A folder that can't be read for a moment, e.g. a network drive reconnecting,
+        // keeps its last snapshot so its flags survive until it is back
+        let files = match scan(&folder).await {
+            Ok(files) => files,
+            Err(e) => {
+                let _ = app.emit("folder-watch-error", e);
+                continue;
+            }
+        };
+
+        // A new profile or folder starts from a fresh scan instead of reporting every file
+        let previous = snapshots.insert(
+            name,
+            Snapshot {
+                folder: folder.clone(),
+                files: files.clone(),
+            },
+        );
+        let previous = match previous {
+            Some(previous) if previous.folder == folder => previous.files,
+            _ => {
+                if name == "posts" {
+                    flag_missing_files(app, &pool, &files).await?;
+                }
+                continue;
+            }
+        };
+
+        for change in diff(&previous, &files) {
+            let event = if name == "posts" {
+                reconcile_post_file(app, &pool, &profile, &folder, change).await?
+            } else {
+                FolderChange {
+                    folder: name.to_string(),
+                    kind: change.kind.to_string(),
+                    file_name: change.file_name,
+                    previous_file_name: change.previous_file_name,
+                    blog_post_ids: Vec::new(),
+                    created_post: false,
+                }
+            };
+            let _ = app.emit("folder-change", event);
+        }
+    }
+    Ok(())
+}
+
+async fn posts_using(pool: &PgPool, file_name: &str) -> Result<Vec<(i32, String)>, String> {
+    let posts = sqlx::query!(
+        "SELECT id, title FROM tadgh_blog.blog_posts WHERE file_name = $1 ORDER BY id",
+        file_name
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(posts
+        .into_iter()
+        .map(|post| (post.id, post.title))
+        .collect())
+}
+
+async fn set_flag(
+    app: &AppHandle,
+    post: (i32, String),
+    file_name: &str,
+    kind: &str,
+    renamed_to: Option<String>,
+) {
+    let watcher = app.state::<FolderWatcher>();
+    watcher.flags.lock().await.insert(
+        post.0,
+        FileFlag {
+            blog_post_id: post.0,
+            title: post.1,
+            file_name: file_name.to_string(),
+            kind: kind.to_string(),
+            renamed_to,
+            detected_at: Utc::now(),
+        },
+    );
+}
+
+// Replaces the flags of the previous profile with posts whose file is already gone
+async fn flag_missing_files(
+    app: &AppHandle,
+    pool: &PgPool,
+    files: &HashMap<String, FileState>,
+) -> Result<(), String> {
+    app.state::<FolderWatcher>().flags.lock().await.clear();
+
+    let posts = sqlx::query!("SELECT id, title, file_name FROM tadgh_blog.blog_posts ORDER BY id")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    for post in posts {
+        if !files.contains_key(&post.file_name) {
+            set_flag(app, (post.id, post.title), &post.file_name, "missing", None).await;
+        }
+    }
+    Ok(())
+}
+
+async fn reconcile_post_file(
+    app: &AppHandle,
+    pool: &PgPool,
+    profile: &Profile,
+    folder: &Path,
+    change: Change,
+) -> Result<FolderChange, String> {
+    let watched = change
+        .previous_file_name
+        .as_deref()
+        .unwrap_or(&change.file_name);
+    let posts = posts_using(pool, watched).await?;
+    let mut blog_post_ids: Vec<i32> = posts.iter().map(|(id, _)| *id).collect();
+    let mut created_post = false;
+
+    let app_write = app.state::<FolderWatcher>().is_app_write(&change.file_name);
+
+    match change.kind {
+        // The app already keeps its own writes in step with the database
+        _ if app_write => {}
+        "deleted" => {
+            for post in posts {
+                set_flag(app, post, &change.file_name, "missing", None).await;
+            }
+        }
+        "renamed" => {
+            for post in posts {
+                set_flag(
+                    app,
+                    post,
+                    watched,
+                    "renamed",
+                    Some(change.file_name.clone()),
+                )
+                .await;
+            }
+        }
+        "modified" if !posts.is_empty() => {
+            let body = tokio::fs::read_to_string(folder.join(&change.file_name))
+                .await
+                .ok();
+            let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+            for post in posts {
+                index_blog_body(&mut conn, post.0, body.as_deref()).await?;
+                set_flag(app, post, &change.file_name, "modified", None).await;
+            }
+        }
+        // A file coming back clears the flags raised when it went missing
+        "created" if !posts.is_empty() => {
+            let watcher = app.state::<FolderWatcher>();
+            let mut flags = watcher.flags.lock().await;
+            for (id, _) in &posts {
+                if flags.get(id).is_some_and(|flag| flag.kind == "missing") {
+                    flags.remove(id);
+                }
+            }
+        }
+        "created" if profile.auto_create_posts() && change.file_name.ends_with(".md") => {
+            let markdown = tokio::fs::read_to_string(folder.join(&change.file_name))
+                .await
+                .map_err(|e| format!("Could not read {}: {}", change.file_name, e))?;
+            let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+            match insert_draft_from_file(&mut tx, &change.file_name, &markdown).await {
+                Ok(created) => {
+                    tx.commit().await.map_err(|e| e.to_string())?;
+                    blog_post_ids.push(created.id());
+                    created_post = true;
+                }
+                Err(e) => {
+                    let _ = app.emit(
+                        "folder-watch-error",
+                        format!("Could not create a post for {}: {}", change.file_name, e),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(FolderChange {
+        folder: "posts".to_string(),
+        kind: change.kind.to_string(),
+        file_name: change.file_name,
+        previous_file_name: change.previous_file_name,
+        blog_post_ids,
+        created_post,
+    })
+}
+
+#[tauri::command]
+pub async fn get_file_flags(watcher: State<'_, FolderWatcher>) -> Result<Vec<FileFlag>, String> {
+    let mut flags: Vec<FileFlag> = watcher.flags.lock().await.values().cloned().collect();
+    flags.sort_by_key(|flag| (flag.detected_at, flag.blog_post_id));
+    Ok(flags)
+}
+
+#[tauri::command]
+pub async fn dismiss_file_flag(
+    watcher: State<'_, FolderWatcher>,
+    blog_post_id: i32,
+) -> Result<(), String> {
+    watcher.flags.lock().await.remove(&blog_post_id);
+    Ok(())
+}
+
+// Points a post at another markdown file, by default the one its file was renamed to.
+// Links built from the old file name are redirected to the post.
+#[tauri::command]
+pub async fn relink_blog_post_file(
+    state: State<'_, Mutex<AppState>>,
+    watcher: State<'_, FolderWatcher>,
+    blog_post_id: i32,
+    file_name: Option<String>,
+) -> Result<(), String> {
+    let file_name = match file_name {
+        Some(file_name) => file_name,
+        None => watcher
+            .flags
+            .lock()
+            .await
+            .get(&blog_post_id)
+            .and_then(|flag| flag.renamed_to.clone())
+            .ok_or("No renamed file to relink the post to")?,
+    };
+    Validator::default()
+        .required("file_name", &file_name)
+        .file_name("file_name", Some(&file_name))
+        .finish()?;
+
+    let state = state.lock().await;
+    let pool = state.pool.as_ref().ok_or("Database not connected")?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let previous = sqlx::query!(
+        "SELECT slug, file_name FROM tadgh_blog.blog_posts WHERE id = $1 FOR UPDATE",
+        blog_post_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or("Blog post not found")?;
+
+    sqlx::query!(
+        "UPDATE tadgh_blog.blog_posts SET file_name = $1 WHERE id = $2",
+        file_name,
+        blog_post_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if file_name != previous.file_name {
+        let old_path = file_permalink(&previous.file_name);
+        let current_path = permalink("blog_post", &previous.slug);
+        record_redirect(&mut tx, &old_path, &current_path, "blog_post", blog_post_id).await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    watcher.flags.lock().await.remove(&blog_post_id);
+    Ok(())
+}
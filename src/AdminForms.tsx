@@ -3,6 +3,7 @@ import { invoke } from "@tauri-apps/api/core";
 import { open as openFs } from "@tauri-apps/plugin-fs";
 import TagSelector from "./TagSelector";
 import { BlogPost, Project, Settings, Tag } from "./interfaces";
+import { describeError } from "./errors";
 import {
 	ActionButton,
 	ContentCard,
@@ -169,17 +170,11 @@ const AdminForms = () => {
 				file_name: blogFileName || blogPost.file_name,
 			};
 
-			const createdBlogData = await invoke<BlogPost>("create_blog_post", {
+			await invoke<BlogPost>("create_blog_post_with_tags", {
 				blogPost: formattedBlogPost,
+				tags: { tag_ids: blogSelectedTags.map((tag) => tag.id) },
 			});
 
-			if (blogSelectedTags.length > 0) {
-				await invoke("add_tags_to_blog", {
-					blogId: createdBlogData.id,
-					tagIds: blogSelectedTags.map((tag) => tag.id),
-				});
-			}
-
 			if (blogImage) {
 				await uploadFile(blogImage, imageFileName, settings.blog_images_path);
 			}
@@ -192,7 +187,7 @@ const AdminForms = () => {
 			resetBlogForm();
 			setSuccessMessage("Blog post created successfully!");
 		} catch (err) {
-			setError(`Failed to create blog post: ${err}`);
+			setError(`Failed to create blog post: ${describeError(err)}`);
 		} finally {
 			setLoading(false);
 		}
@@ -214,17 +209,11 @@ const AdminForms = () => {
 				image_name: projectImageFileName || project.image_name,
 			};
 
-			const createdProjectData = await invoke<Project>("create_project", {
+			await invoke<Project>("create_project_with_tags", {
 				project: formattedProject,
+				tags: { tag_ids: projectSelectedTags.map((tag) => tag.id) },
 			});
 
-			if (projectSelectedTags.length > 0) {
-				await invoke("add_tags_to_project", {
-					projectId: createdProjectData.id,
-					tagIds: projectSelectedTags.map((tag) => tag.id),
-				});
-			}
-
 			if (projectImage) {
 				await uploadFile(
 					projectImage,
@@ -237,7 +226,7 @@ const AdminForms = () => {
 			resetProjectForm();
 			setSuccessMessage("Project created successfully!");
 		} catch (err) {
-			setError(`Failed to create project: ${err}`);
+			setError(`Failed to create project: ${describeError(err)}`);
 		} finally {
 			setLoading(false);
 		}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { Tag, Category } from "./interfaces";
+import { describeError } from "./errors";
 import TagSelector from "./TagSelector";
 import { LoadingSpinner, ContentCard, SearchInput } from "./components";
 import { Notification, ConfirmationDialog } from "./components/index";
@@ -72,7 +73,7 @@ const CategoryManagement = () => {
 			setNewCategoryDescription("");
 			setSuccessMessage(`Category "${newCategory.name}" created successfully!`);
 		} catch (err) {
-			setError("Failed to create category");
+			setError(`Failed to create category: ${describeError(err)}`);
 			console.error(err);
 		}
 	};
@@ -103,7 +104,7 @@ const CategoryManagement = () => {
 			);
 			setSuccessMessage(`Category "${category.name}" updated successfully!`);
 		} catch (err) {
-			setError("Failed to update category");
+			setError(`Failed to update category: ${describeError(err)}`);
 		}
 	};
 
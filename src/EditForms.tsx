@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Tag, Settings } from "./interfaces";
+import { Tag, Settings, Page } from "./interfaces";
+import { describeError } from "./errors";
 import {
 	ContentCard,
 	SearchInput,
@@ -67,10 +68,12 @@ const EditForms = () => {
 	const fetchData = async () => {
 		setLoading(true);
 		try {
-			const [blogData, projectData] = await Promise.all([
-				invoke<BlogPost[]>("get_blog_posts"),
-				invoke<Project[]>("get_projects"),
+			const [blogPage, projectPage] = await Promise.all([
+				invoke<Page<BlogPost>>("get_blog_posts"),
+				invoke<Page<Project>>("get_projects"),
 			]);
+			const blogData = blogPage.items;
+			const projectData = projectPage.items;
 
 			// Fetch tags for each blog post
 			const blogTags = await Promise.all(
@@ -134,7 +137,7 @@ const EditForms = () => {
 			setSuccessMessage("Blog post updated successfully!");
 			await fetchData(); // Refresh data after update
 		} catch (err) {
-			setError(`Failed to update blog post: ${err}`);
+			setError(`Failed to update blog post: ${describeError(err)}`);
 		} finally {
 			setLoading(false);
 		}
@@ -185,7 +188,7 @@ const EditForms = () => {
 			setSuccessMessage("Project updated successfully!");
 			await fetchData(); // Refresh data after update
 		} catch (err) {
-			setError(`Failed to update project: ${err}`);
+			setError(`Failed to update project: ${describeError(err)}`);
 		} finally {
 			setLoading(false);
 		}
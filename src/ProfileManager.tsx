@@ -22,6 +22,9 @@ interface ProfileFormData {
 	database_connection: DatabaseConnectionInfo;
 	blog_images_path: string;
 	blog_folder_path: string;
+	site_url: string;
+	site_title: string;
+	site_output_path: string;
 }
 
 export const ProfileManager: React.FC<ProfileManagerProps> = ({
@@ -45,6 +48,9 @@ export const ProfileManager: React.FC<ProfileManagerProps> = ({
 		},
 		blog_images_path: "",
 		blog_folder_path: "",
+		site_url: "",
+		site_title: "",
+		site_output_path: "",
 	});
 
 	useEffect(() => {
@@ -70,11 +76,16 @@ export const ProfileManager: React.FC<ProfileManagerProps> = ({
 		}
 
 		try {
+			// Keep settings this form doesn't edit, like the deploy hook
 			const profile: Profile = {
+				...editingProfile,
 				name: formData.name.trim(),
 				database_connection: formData.database_connection,
 				blog_images_path: formData.blog_images_path || null,
 				blog_folder_path: formData.blog_folder_path || null,
+				site_url: formData.site_url.trim() || null,
+				site_title: formData.site_title.trim() || null,
+				site_output_path: formData.site_output_path || null,
 			};
 
 			await invoke("save_profile", { profile });
@@ -108,6 +119,9 @@ export const ProfileManager: React.FC<ProfileManagerProps> = ({
 			},
 			blog_images_path: "",
 			blog_folder_path: "",
+			site_url: "",
+			site_title: "",
+			site_output_path: "",
 		});
 		setShowCreateModal(false);
 		setEditingProfile(null);
@@ -120,6 +134,9 @@ export const ProfileManager: React.FC<ProfileManagerProps> = ({
 			database_connection: { ...profile.database_connection },
 			blog_images_path: profile.blog_images_path || "",
 			blog_folder_path: profile.blog_folder_path || "",
+			site_url: profile.site_url || "",
+			site_title: profile.site_title || "",
+			site_output_path: profile.site_output_path || "",
 		});
 		setShowCreateModal(true);
 	};
@@ -147,15 +164,19 @@ export const ProfileManager: React.FC<ProfileManagerProps> = ({
 	};
 
 	const selectDirectory = async (
-		field: "blog_images_path" | "blog_folder_path"
+		field: "blog_images_path" | "blog_folder_path" | "site_output_path"
 	) => {
+		const titles = {
+			blog_images_path: "Images",
+			blog_folder_path: "Files",
+			site_output_path: "Output",
+		};
+
 		try {
 			const result = await open({
 				directory: true,
 				multiple: false,
-				title: `Select ${
-					field === "blog_images_path" ? "Images" : "Files"
-				} Directory`,
+				title: `Select ${titles[field]} Directory`,
 			});
 
 			if (result) {
@@ -434,6 +455,60 @@ export const ProfileManager: React.FC<ProfileManagerProps> = ({
 							</div>
 						</div>
 
+						{/* Site */}
+						<div>
+							<h4 className="mb-3 text-sm font-medium text-gray-700">Site</h4>
+							<div className="space-y-4">
+								<div>
+									<label className="block mb-1 text-xs text-gray-600">
+										Site URL
+									</label>
+									<input
+										type="text"
+										name="site_url"
+										value={formData.site_url}
+										onChange={handleInputChange}
+										className="px-3 py-2 w-full rounded-md border border-gray-300 focus:outline-none focus:ring-2 focus:ring-blue-500"
+										placeholder="https://example.com"
+									/>
+								</div>
+								<div>
+									<label className="block mb-1 text-xs text-gray-600">
+										Site Title
+									</label>
+									<input
+										type="text"
+										name="site_title"
+										value={formData.site_title}
+										onChange={handleInputChange}
+										className="px-3 py-2 w-full rounded-md border border-gray-300 focus:outline-none focus:ring-2 focus:ring-blue-500"
+										placeholder="Used as the feed title"
+									/>
+								</div>
+								<div>
+									<label className="block mb-1 text-xs text-gray-600">
+										Output Directory
+									</label>
+									<div className="flex gap-2">
+										<input
+											type="text"
+											value={formData.site_output_path}
+											readOnly
+											className="flex-1 px-3 py-2 bg-gray-50 rounded-md border border-gray-300"
+											placeholder="Select directory for feeds and sitemaps"
+										/>
+										<button
+											type="button"
+											onClick={() => selectDirectory("site_output_path")}
+											className="flex items-center px-3 py-2 text-sm font-medium text-white bg-blue-600 rounded-md hover:bg-blue-700">
+											<FolderIcon className="mr-2 w-4 h-4" />
+											Browse
+										</button>
+									</div>
+								</div>
+							</div>
+						</div>
+
 						{/* Modal Actions */}
 						<div className="flex gap-3 justify-end pt-4 border-t">
 							<ActionButton onClick={resetForm} variant="danger">
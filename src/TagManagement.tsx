@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { Tag } from "./interfaces";
+import { describeError } from "./errors";
 import { LoadingSpinner, SearchInput, ContentCard } from "./components";
 import { Notification, ConfirmationDialog } from "./components/index";
 
@@ -51,7 +52,7 @@ const TagManagement = () => {
 			setNewTagName("");
 			setSuccessMessage(`Tag "${newTag.name}" created successfully!`);
 		} catch (err) {
-			setError("Failed to create tag");
+			setError(`Failed to create tag: ${describeError(err)}`);
 			console.error(err);
 		} finally {
 			setLoading(false);
@@ -80,7 +81,7 @@ const TagManagement = () => {
 			);
 			setSuccessMessage(`Tag "${tag.name}" updated successfully!`);
 		} catch (err) {
-			setError("Failed to update tag");
+			setError(`Failed to update tag: ${describeError(err)}`);
 			console.error(err);
 		} finally {
 			setLoading(false);
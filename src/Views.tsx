@@ -8,6 +8,7 @@ import {
 	SearchInput,
 } from "./components";
 import { Modal, Notification } from "./components/index";
+import { Page } from "./interfaces";
 import {
 	ImageIcon,
 	EyeIcon,
@@ -73,10 +74,10 @@ const Views = () => {
 	const fetchData = async () => {
 		setLoading(true);
 		try {
-			const posts = await invoke<BlogPostWithViews[]>(
+			const posts = await invoke<Page<BlogPostWithViews>>(
 				"get_blog_posts_with_views"
 			);
-			setBlogPosts(posts);
+			setBlogPosts(posts.items);
 		} catch (err) {
 			setError(`Failed to fetch blog posts: ${err}`);
 		} finally {
@@ -126,12 +127,12 @@ const Views = () => {
 
 	const fetchPostViews = async (blogPostId: number) => {
 		try {
-			const views = await invoke<BlogPostView[]>("get_blog_post_views", {
+			const views = await invoke<Page<BlogPostView>>("get_blog_post_views", {
 				blogPostId,
 			});
 			setPostViews((prev) => ({
 				...prev,
-				[blogPostId]: views,
+				[blogPostId]: views.items,
 			}));
 		} catch (err) {
 			setError(`Failed to fetch post views: ${err}`);
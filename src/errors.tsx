@@ -0,0 +1,23 @@
+import { FieldError, ValidationErrors } from "./interfaces";
+
+// Per-field errors from a failed command, or null for any other error
+export function fieldErrors(err: unknown): FieldError[] | null {
+	if (typeof err !== "string") {
+		return null;
+	}
+	try {
+		const parsed = JSON.parse(err) as ValidationErrors;
+		return Array.isArray(parsed.field_errors) ? parsed.field_errors : null;
+	} catch {
+		return null;
+	}
+}
+
+// Readable message for a command error, listing each invalid field
+export function describeError(err: unknown): string {
+	const errors = fieldErrors(err);
+	if (!errors) {
+		return String(err);
+	}
+	return errors.map((e) => `${e.field.replace(/_/g, " ")}: ${e.message}`).join("; ");
+}
@@ -5,6 +5,31 @@ export interface BlogPost {
 	description: string; // Required
 	image_path: string | null; // Optional
 	file_name: string; // Required
+	published?: boolean; // Left out on update to keep the current status
+	slug?: string | null; // Left out on update to keep the current slug
+}
+
+export interface Page<T> {
+	items: T[];
+	total: number;
+	page: number;
+	page_size: number | null;
+	next_cursor: string | null;
+}
+
+export interface ListOptions {
+	page?: number;
+	page_size?: number;
+	cursor?: string;
+	sort_by?: string;
+	sort_direction?: "asc" | "desc";
+	date_from?: string;
+	date_to?: string;
+	tag_ids?: number[];
+	category_ids?: number[];
+	author_ids?: number[];
+	statuses?: string[];
+	text?: string;
 }
 
 export interface BlogPostWithViews {
@@ -48,6 +73,12 @@ export interface Profile {
 	blog_images_path: string | null;
 	blog_folder_path: string | null;
 	created_at?: string | null;
+	deploy_hook?: string | null;
+	site_url?: string | null;
+	site_title?: string | null;
+	site_output_path?: string | null;
+	custom_dictionary?: string[]; // Extra words the spell checker accepts
+	auto_create_posts?: boolean; // Create draft posts for new files in the blog folder
 }
 
 export interface Settings {
@@ -76,3 +107,148 @@ export interface Category {
 	name: string;
 	description?: string | null;
 }
+
+export interface FieldError {
+	field: string;
+	message: string;
+}
+
+// Error returned by create and update commands when their input fails validation
+export interface ValidationErrors {
+	field_errors: FieldError[];
+}
+
+// Tags for the *_with_tags commands; names that don't match an existing tag are created
+export interface TagSelection {
+	tag_ids?: number[];
+	tag_names?: string[];
+}
+
+export interface AuthorLink {
+	label: string;
+	url: string;
+}
+
+export interface Author {
+	id: number;
+	name: string;
+	slug: string;
+	bio: string | null;
+	avatar_name: string | null; // File in the blog images folder
+	links: AuthorLink[];
+}
+
+// Search and link preview overrides; unset fields fall back to the post or project
+export interface Seo {
+	meta_title: string | null;
+	meta_description: string | null;
+	canonical_url: string | null;
+	og_image: string | null;
+	noindex: boolean;
+}
+
+export interface SeoIssue {
+	severity: "error" | "warning" | "info";
+	kind: string;
+	entity_type: "blog_post" | "project";
+	entity_id: number;
+	title: string;
+	field: string;
+	message: string;
+}
+
+export interface SpellingDiagnostic {
+	kind: "spelling" | "repeated_word" | "passive_voice" | "wordy_phrase" | "dictionary_missing";
+	severity: "error" | "warning" | "info";
+	line: number;
+	column: number;
+	end_line: number;
+	end_column: number;
+	text: string;
+	message: string;
+	suggestions: string[];
+}
+
+export interface FolderChange {
+	folder: "posts" | "images";
+	kind: "created" | "modified" | "deleted" | "renamed";
+	file_name: string;
+	previous_file_name: string | null;
+	blog_post_ids: number[];
+	created_post: boolean;
+}
+
+export interface FileFlag {
+	blog_post_id: number;
+	title: string;
+	file_name: string;
+	kind: "missing" | "modified" | "renamed";
+	renamed_to: string | null;
+	detected_at: string;
+}
+
+export interface Comment {
+	id: number;
+	blog_post_id: number;
+	blog_post_title: string;
+	parent_id: number | null;
+	author_name: string;
+	author_email: string | null;
+	author_url: string | null;
+	ip_address: string | null;
+	body: string;
+	status: "pending" | "approved" | "rejected" | "spam";
+	is_admin_reply: boolean;
+	spam_score: number; // 0 to 100, 50 and above is likely spam
+	spam_reasons: string[];
+	scored_at: string | null;
+	created_at: string;
+	moderated_at: string | null;
+}
+
+export interface PostCommentCounts {
+	blog_post_id: number;
+	title: string;
+	total: number;
+	pending: number;
+	approved: number;
+	rejected: number;
+	spam: number;
+}
+
+export interface SpamScoreReport {
+	scored: number;
+	likely_spam: number;
+	marked_spam: number;
+}
+
+export interface Translation {
+	id: number;
+	blog_post_id: number;
+	locale: string; // e.g. "fr" or "fr-CA"
+	title: string;
+	description: string;
+	file_name: string;
+	slug: string;
+	published: boolean;
+	created_at: string;
+	updated_at: string;
+}
+
+export interface MissingTranslation {
+	blog_post_id: number;
+	title: string;
+	slug: string | null;
+	locale: string;
+	created: string | null;
+	published: boolean;
+	translated_locales: string[];
+}
+
+export interface MigrationStatus {
+	version: number;
+	description: string;
+	applied: boolean;
+	failed: boolean; // Started but didn't finish, needs fixing by hand
+	sql: string;
+}
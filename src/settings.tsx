@@ -88,7 +88,9 @@ export default function SettingsPage() {
 		}
 
 		try {
+			// Keep the settings this form doesn't show, like the custom dictionary
 			const profile: Profile = {
+				...editingProfile,
 				name: formData.name.trim(),
 				database_connection: formData.database_connection,
 				blog_images_path: formData.blog_images_path || null,